@@ -9,7 +9,7 @@ use quote::quote;
 // taken in parts from
 // https://doc.rust-lang.org/book/ch19-06-macros.html#how-to-write-a-custom-derive-macro
 
-#[proc_macro_derive(Key)]
+#[proc_macro_derive(Key, attributes(miniarg))]
 pub fn key_derive(input: TokenStream) -> TokenStream {
     // Construct a representation of Rust code as a syntax tree
     // that we can manipulate
@@ -21,10 +21,98 @@ pub fn key_derive(input: TokenStream) -> TokenStream {
 
 fn impl_key(ast: &syn::DeriveInput) -> TokenStream {
     let name = &ast.ident;
-    let syn::Data::Enum(data) = &ast.data else { panic!("only enums are supported") };
+    let syn::Data::Enum(data) = &ast.data else {
+        panic!("only enums are supported")
+    };
     let mut variants = syn::punctuated::Punctuated::<_, syn::token::Comma>::new();
     let mut help_strings = Vec::new();
-    for variant in &data.variants {
+    let mut help_entries = Vec::new();
+    let mut required = Vec::new();
+    let mut occurrences = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut requires = Vec::new();
+    let mut defaults = Vec::new();
+    let mut env = Vec::new();
+    let mut flags = Vec::new();
+    let mut arities = Vec::new();
+    let mut hyphen_values = Vec::new();
+    let mut deprecated = Vec::new();
+    let mut man_keys = Vec::new();
+    let mut usage = Vec::new();
+    let keys: Vec<_> = data
+        .variants
+        .iter()
+        .map(|variant| first_lower(&variant.ident.to_string()))
+        .collect();
+    for (index, variant) in data.variants.iter().enumerate() {
+        let options = match VariantOptions::parse(variant) {
+            Ok(options) => options,
+            Err(e) => return e.to_compile_error().into(),
+        };
+        // this has to match `MAX_TRACKED` in miniarg
+        if index >= 128 && options.is_tracked() {
+            return syn::Error::new_spanned(
+                &variant.ident,
+                "only the first 128 keys can be required, conflict, be deprecated or have a default or env",
+            )
+            .to_compile_error()
+            .into();
+        }
+        let key = first_lower(&variant.ident.to_string());
+        if options.required {
+            required.push(key.clone());
+        }
+        let limit = match (options.min, options.max) {
+            (None, None) => None,
+            (Some(min), None) => Some(quote! { miniarg::Occurrences::at_least(#min) }),
+            (None, Some(max)) => Some(quote! { miniarg::Occurrences::at_most(#max) }),
+            (Some(min), Some(max)) => Some(quote! { miniarg::Occurrences::between(#min, #max) }),
+        };
+        if let Some(limit) = limit {
+            occurrences.push(quote! { (#key, #limit) });
+        }
+        for other in options.conflicts_with.iter().chain(&options.requires) {
+            match keys.iter().position(|key| *key == other.value()) {
+                None => {
+                    return syn::Error::new(other.span(), "unknown key")
+                        .to_compile_error()
+                        .into();
+                }
+                Some(index) if index >= 128 => {
+                    return syn::Error::new(other.span(), "only the first 128 keys can be referenced")
+                        .to_compile_error()
+                        .into();
+                }
+                Some(_) => {}
+            }
+        }
+        let conflicts_with = &options.conflicts_with;
+        conflicts.extend(conflicts_with.iter().map(|other| quote! { (#key, #other) }));
+        let dependencies = &options.requires;
+        requires.extend(dependencies.iter().map(|other| quote! { (#key, #other) }));
+        if !options.hidden {
+            usage.push(options.usage(&key));
+        }
+        if let Some(note) = &options.deprecated {
+            deprecated.push(quote! { (#key, #note) });
+        }
+        if options.flag {
+            flags.push(key.clone());
+        }
+        if options.allow_hyphen_values {
+            hyphen_values.push(key.clone());
+        }
+        if options.greedy {
+            arities.push(quote! { (#key, miniarg::Arity::Greedy) });
+        } else if let Some(arity) = options.arity {
+            arities.push(quote! { (#key, miniarg::Arity::Exactly(#arity)) });
+        }
+        if let Some(var) = &options.env {
+            env.push(quote! { (#key, #var) });
+        }
+        if let Some(default) = &options.default {
+            defaults.push(quote! { (#key, #default) });
+        }
         let mut path = syn::punctuated::Punctuated::<syn::PathSegment, syn::token::PathSep>::new();
         path.push(syn::PathSegment {
             ident: syn::token::SelfType {
@@ -41,32 +129,58 @@ fn impl_key(ast: &syn::DeriveInput) -> TokenStream {
             leading_colon: None,
             segments: path,
         });
-        let mut doc = String::new();
-        for attr in &variant.attrs {
-            if let syn::Meta::NameValue(mnv) = &attr.meta {
-                if mnv.path.is_ident("doc") {
-                    match &mnv.value {
-                        syn::Expr::Lit(l) => {
-                            if let syn::Lit::Str(s) = &l.lit {
-                                doc = s.value();
-                                break;
-                            }
-                            panic!("failed to parse {l:?}");
-                        }
-                        _ => {
-                            panic!("failed to parse {mnv:?}");
-                        }
-                    }
-                }
+        let mut doc = doc(&variant.attrs);
+        if !options.hidden {
+            let mut man_key = quote! { miniarg::man::ManKey::new(#key, #doc) };
+            match options.value_names("", "") {
+                Some(value_names) => man_key.extend(quote! { .value_name(#value_names) }),
+                None => man_key.extend(quote! { .flag() }),
+            }
+            if let Some(var) = &options.env {
+                man_key.extend(quote! { .env(#var) });
+            }
+            if let Some(default) = &options.default {
+                man_key.extend(quote! { .default(#default) });
             }
+            man_keys.push(man_key);
         }
-        help_strings.push(format!(
-            "-{}\t{}",
-            first_lower(&variant.ident.to_string()),
-            doc
-        ));
+        let notes = options
+            .env
+            .iter()
+            .map(|var| format!("[env: {}]", var.value()))
+            .chain(
+                options
+                    .default
+                    .iter()
+                    .map(|default| format!("[default: {}]", default.value())),
+            );
+        for note in notes {
+            if !doc.is_empty() {
+                doc.push(' ');
+            }
+            doc.push_str(&note);
+        }
+        if !options.hidden {
+            // further paragraphs are indented like the first one
+            help_strings.push(format!("-{key}\t{}", doc.replace('\n', "\n\t")));
+            help_entries.push(quote! { (#key, #doc) });
+        }
+    }
+    // this has to match `MAX_OCCURRENCES` in miniarg
+    if occurrences.len() > 32 {
+        return syn::Error::new_spanned(name, "at most 32 keys can have occurrence limits")
+            .to_compile_error()
+            .into();
     }
-    let help_text = help_strings.join("\n");
+    let help_text = help_text(&ast.attrs, &help_strings);
+    let description = doc(&ast.attrs);
+    let usage_args = usage.join(" ");
+    let usage = if usage_args.is_empty() {
+        String::new()
+    } else {
+        format!(" {usage_args}")
+    };
+    let variant_paths = variants.iter();
     let generated = quote! {
         impl fmt::Display for #name {
             fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -75,18 +189,369 @@ fn impl_key(ast: &syn::DeriveInput) -> TokenStream {
         }
 
         impl Key for #name {
-            fn parse(cmdline: &str) -> ArgumentIterator<Self, miniarg::split_args::SplitArgs> {
-                miniarg::parse(cmdline, &[#variants])
+            fn parse_from_iter<'a, S>(args: S) -> miniarg::ArgumentIterator<'a, 'a, Self, S>
+            where
+                S: Iterator,
+                S::Item: miniarg::Argument<'a>,
+            {
+                Self::configure(miniarg::parse_from_iter(args, &[#variants]))
+            }
+
+            fn configure<'a, 'b, T, S>(
+                args: miniarg::ArgumentIterator<'a, 'b, T, S>,
+            ) -> miniarg::ArgumentIterator<'a, 'b, T, S>
+            where
+                T: miniarg::__private::ToString,
+                S: Iterator,
+                S::Item: miniarg::Argument<'a>,
+            {
+                const OCCURRENCES: &[(&str, miniarg::Occurrences)] = &[#(#occurrences),*];
+                args.required(&[#(#required),*])
+                    .occurrences(OCCURRENCES)
+                    .conflicts(&[#(#conflicts),*])
+                    .requires(&[#(#requires),*])
+                    .defaults(&[#(#defaults),*])
+                    .env(&[#(#env),*])
+                    .flags(&[#(#flags),*])
+                    .arity(&[#(#arities),*])
+                    .allow_hyphen_values(&[#(#hyphen_values),*])
+                    .deprecated(&[#(#deprecated),*])
+            }
+
+            fn keys() -> &'static [&'static str] {
+                &[#(#keys),*]
+            }
+
+            fn from_key(name: &str) -> Option<Self> {
+                match name {
+                    #(#keys => Some(#variant_paths),)*
+                    _ => None,
+                }
+            }
+
+            fn parse_config(config: &str) -> miniarg::config::ConfigIterator<'_, 'static, Self> {
+                miniarg::config::parse_config(config, &[#variants]).flags(&[#(#flags),*])
             }
 
             fn help_text() -> &'static str {
                 #help_text
             }
+
+            fn description() -> &'static str {
+                #description
+            }
+
+            fn help_entries() -> &'static [(&'static str, &'static str)] {
+                &[#(#help_entries),*]
+            }
+
+            fn usage() -> &'static str {
+                concat!("usage: ", env!("CARGO_PKG_NAME"), #usage)
+            }
+
+            fn version() -> &'static str {
+                concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION"))
+            }
+
+            fn man_page() -> miniarg::man::ManPage<'static> {
+                const KEYS: &[miniarg::man::ManKey<'static>] = &[#(#man_keys),*];
+                miniarg::man::ManPage::new(env!("CARGO_PKG_NAME"), KEYS)
+                    .description(#description)
+                    .synopsis(#usage_args)
+            }
+
+            fn usage_args() -> &'static str {
+                #usage_args
+            }
         }
     };
     generated.into()
 }
 
+#[proc_macro_derive(Subcommand)]
+pub fn subcommand_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse(input).unwrap();
+    impl_subcommand(&ast)
+}
+
+fn impl_subcommand(ast: &syn::DeriveInput) -> TokenStream {
+    let name = &ast.ident;
+    let syn::Data::Enum(data) = &ast.data else {
+        panic!("only enums are supported")
+    };
+    let mut idents = Vec::new();
+    let mut types = Vec::new();
+    let mut help_strings = Vec::new();
+    for variant in &data.variants {
+        let syn::Fields::Unnamed(fields) = &variant.fields else {
+            return syn::Error::new_spanned(variant, "expected a kind wrapping a Key")
+                .to_compile_error()
+                .into();
+        };
+        let Some(field) = fields.unnamed.first().filter(|_| fields.unnamed.len() == 1) else {
+            return syn::Error::new_spanned(fields, "expected exactly one field")
+                .to_compile_error()
+                .into();
+        };
+        idents.push(&variant.ident);
+        types.push(&field.ty);
+        let subcommand = first_lower(&variant.ident.to_string());
+        let doc = doc(&variant.attrs).replace('\n', "\n\t");
+        help_strings.push(format!("{subcommand}\t{doc}"));
+    }
+    let names: Vec<_> = idents
+        .iter()
+        .map(|ident| first_lower(&ident.to_string()))
+        .collect();
+    let indices: Vec<_> = (0..idents.len())
+        .map(proc_macro2::Literal::usize_unsuffixed)
+        .collect();
+    let help_text = help_text(&ast.attrs, &help_strings);
+    let generated = quote! {
+        impl Subcommand for #name {
+            fn subcommands() -> &'static [&'static str] {
+                &[#(#names),*]
+            }
+
+            fn keys(index: usize) -> &'static [&'static str] {
+                match index {
+                    #(#indices => <#types as miniarg::Key>::keys(),)*
+                    _ => &[],
+                }
+            }
+
+            fn configure<'a, 'b, T, S>(
+                index: usize,
+                args: miniarg::ArgumentIterator<'a, 'b, T, S>,
+            ) -> miniarg::ArgumentIterator<'a, 'b, T, S>
+            where
+                T: miniarg::__private::ToString,
+                S: Iterator,
+                S::Item: miniarg::Argument<'a>,
+            {
+                match index {
+                    #(#indices => <#types as miniarg::Key>::configure(args),)*
+                    _ => args,
+                }
+            }
+
+            fn from_key(index: usize, name: &str) -> Option<Self> {
+                match index {
+                    #(#indices => <#types as miniarg::Key>::from_key(name).map(Self::#idents),)*
+                    _ => None,
+                }
+            }
+
+            fn help_text() -> &'static str {
+                #help_text
+            }
+
+            fn subcommand_help_text(name: &str) -> Option<&'static str> {
+                match name {
+                    #(#names => Some(<#types as miniarg::Key>::help_text()),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+    generated.into()
+}
+
+/// The options given in `#[miniarg(...)]` attributes on a variant.
+#[derive(Default)]
+struct VariantOptions {
+    /// `required`: the key has to occur
+    required: bool,
+    /// `min = N`: the key has to occur at least `N` times
+    min: Option<usize>,
+    /// `max = N` (or `once` for `max = 1`): the key may occur at most `N` times
+    max: Option<usize>,
+    /// `conflicts_with = "key"`: the keys can't be used together
+    conflicts_with: Vec<syn::LitStr>,
+    /// `requires = "key"`: the key can only be used together with the other one
+    requires: Vec<syn::LitStr>,
+    /// `default = "value"`: the value if the key doesn't occur
+    default: Option<syn::LitStr>,
+    /// `env = "VAR"`: the environment variable to use if the key doesn't occur
+    env: Option<syn::LitStr>,
+    /// `flag`: the key doesn't take a value and can be negated
+    flag: bool,
+    /// `arity = N`: the key takes `N` values
+    arity: Option<usize>,
+    /// `greedy`: the key takes all values until the next key
+    greedy: bool,
+    /// `allow_hyphen_values`: the values of the key may look like keys
+    allow_hyphen_values: bool,
+    /// `value_name = "NAME"`: the placeholder for the value in the usage line
+    value_name: Option<syn::LitStr>,
+    /// `hidden`: the key isn't shown in the help text and the usage line
+    hidden: bool,
+    /// `deprecated = "note"`: the key still works, but yields a warning
+    deprecated: Option<syn::LitStr>,
+}
+
+impl VariantOptions {
+    fn parse(variant: &syn::Variant) -> syn::Result<Self> {
+        let mut options = Self::default();
+        for attr in &variant.attrs {
+            if !attr.path().is_ident("miniarg") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("required") {
+                    options.required = true;
+                    Ok(())
+                } else if meta.path.is_ident("once") {
+                    options.max = Some(1);
+                    Ok(())
+                } else if meta.path.is_ident("min") {
+                    options.min = Some(meta.value()?.parse::<syn::LitInt>()?.base10_parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("max") {
+                    options.max = Some(meta.value()?.parse::<syn::LitInt>()?.base10_parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("conflicts_with") {
+                    options.conflicts_with.push(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("requires") {
+                    options.requires.push(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    options.default = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("arity") {
+                    options.arity = Some(meta.value()?.parse::<syn::LitInt>()?.base10_parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("value_name") {
+                    options.value_name = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("allow_hyphen_values") {
+                    options.allow_hyphen_values = true;
+                    Ok(())
+                } else if meta.path.is_ident("greedy") {
+                    options.greedy = true;
+                    Ok(())
+                } else if meta.path.is_ident("flag") {
+                    options.flag = true;
+                    Ok(())
+                } else if meta.path.is_ident("hidden") {
+                    options.hidden = true;
+                    Ok(())
+                } else if meta.path.is_ident("deprecated") {
+                    options.deprecated = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("env") {
+                    options.env = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown miniarg attribute"))
+                }
+            })?;
+        }
+        Ok(options)
+    }
+
+    /// Check whether the key has to be tracked (only the first 128 can be).
+    fn is_tracked(&self) -> bool {
+        self.required
+            || !self.conflicts_with.is_empty()
+            || !self.requires.is_empty()
+            || self.deprecated.is_some()
+            || self.default.is_some()
+            || self.env.is_some()
+    }
+
+    /// Describe the key `key` for the usage line, like `[-key <value>...]`.
+    fn usage(&self, key: &str) -> String {
+        let mut usage = format!("-{key}");
+        if let Some(value_names) = self.value_names("<", ">") {
+            usage += &format!(" {value_names}");
+            // the key can occur multiple times or take multiple values
+            if self.greedy || self.max.is_none_or(|max| max > 1) {
+                usage += "...";
+            }
+        }
+        if self.required || self.min.is_some_and(|min| min > 0) {
+            usage
+        } else {
+            format!("[{usage}]")
+        }
+    }
+
+    /// Get the placeholders for the values (one per value, enclosed in `open` and `close`).
+    ///
+    /// Flags don't have any.
+    fn value_names(&self, open: &str, close: &str) -> Option<String> {
+        if self.flag {
+            return None;
+        }
+        let value_name = self
+            .value_name
+            .as_ref()
+            .map_or_else(|| "value".to_string(), syn::LitStr::value);
+        let values = if self.greedy {
+            1
+        } else {
+            self.arity.unwrap_or(1)
+        };
+        let value_names: Vec<_> = (0..values)
+            .map(|_| format!("{open}{value_name}{close}"))
+            .collect();
+        Some(value_names.join(" "))
+    }
+}
+
+/// Get the documentation comments.
+///
+/// Each line is trimmed, lines of a paragraph are joined with spaces
+/// and paragraphs are separated by newlines.
+fn doc(attrs: &[syn::Attribute]) -> String {
+    let mut paragraphs = vec![String::new()];
+    for attr in attrs {
+        if let syn::Meta::NameValue(mnv) = &attr.meta
+            && mnv.path.is_ident("doc")
+        {
+            let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) = &mnv.value
+            else {
+                // like `include_str!`, this can't be evaluated here
+                continue;
+            };
+            // block comments may contain several lines
+            for line in s.value().split('\n').map(str::trim) {
+                let paragraph = paragraphs.last_mut().unwrap();
+                if line.is_empty() {
+                    if !paragraph.is_empty() {
+                        paragraphs.push(String::new());
+                    }
+                } else {
+                    if !paragraph.is_empty() {
+                        paragraph.push(' ');
+                    }
+                    paragraph.push_str(line);
+                }
+            }
+        }
+    }
+    if paragraphs.last().is_some_and(String::is_empty) {
+        paragraphs.pop();
+    }
+    paragraphs.join("\n")
+}
+
+/// Join the help lines, starting with the documentation of the enum (if any).
+fn help_text(attrs: &[syn::Attribute], lines: &[String]) -> String {
+    let description = doc(attrs);
+    let lines = lines.join("\n");
+    if description.is_empty() {
+        lines
+    } else {
+        format!("{description}\n\n{lines}")
+    }
+}
+
 /// Turn the first character into lowercase.
 // This has to be duplicated because of proc_macro.
 fn first_lower(input: &str) -> String {
@@ -0,0 +1,85 @@
+use crate::{Argument, ArgumentIterator, ParseError, ToString};
+
+/// How many values a key takes in one occurrence.
+///
+/// This is used by [`ArgumentIterator::arity`].
+///
+/// [`ArgumentIterator::arity`]: struct.ArgumentIterator.html#method.arity
+/// [`ArgumentIterator::allow_hyphen_values`]: struct.ArgumentIterator.html#method.allow_hyphen_values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Arity {
+    /// The key takes exactly this many values.
+    ///
+    /// Values that start with a dash follow the rules of
+    /// [`ArgumentIterator::allow_hyphen_values`].
+    Exactly(usize),
+    /// The key takes all values until the next argument starting with a dash
+    /// (but at least one).
+    Greedy,
+}
+
+impl Arity {
+    /// Check whether the key takes more values after `got` of them.
+    pub(crate) const fn takes_more(self, got: usize) -> bool {
+        match self {
+            Self::Exactly(n) => got < n,
+            Self::Greedy => true,
+        }
+    }
+}
+
+impl Default for Arity {
+    /// A key takes exactly one value by default.
+    fn default() -> Self {
+        Self::Exactly(1)
+    }
+}
+
+/// Yields the index of the key occurrence with each value.
+///
+/// This is created by [`ArgumentIterator::grouped`], see there for details.
+///
+/// [`ArgumentIterator::grouped`]: struct.ArgumentIterator.html#method.grouped
+pub struct Grouped<'a, 'b, T, S>
+where
+    T: ToString,
+    S: Iterator,
+    S::Item: Argument<'a>,
+{
+    inner: ArgumentIterator<'a, 'b, T, S>,
+}
+
+impl<'a, 'b, T, S> Grouped<'a, 'b, T, S>
+where
+    T: ToString,
+    S: Iterator,
+    S::Item: Argument<'a>,
+{
+    pub(crate) const fn new(inner: ArgumentIterator<'a, 'b, T, S>) -> Self {
+        Self { inner }
+    }
+
+    /// Get the wrapped iterator.
+    pub const fn inner(&self) -> &ArgumentIterator<'a, 'b, T, S> {
+        &self.inner
+    }
+}
+
+impl<'a, 'b, T, S> Iterator for Grouped<'a, 'b, T, S>
+where
+    T: ToString,
+    S: Iterator,
+    S::Item: Argument<'a>,
+{
+    type Item = Result<
+        (&'b T, usize, <S::Item as Argument<'a>>::Str),
+        ParseError<'a, <S::Item as Argument<'a>>::Str>,
+    >;
+
+    /// Get the next key, the index of its occurrence and the value, or an error.
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.inner.next()?;
+        // each value follows its key, so that has been counted
+        Some(item.map(|(option, value)| (option, self.inner.keys_seen - 1, value)))
+    }
+}
@@ -0,0 +1,105 @@
+use core::iter::FusedIterator;
+
+use crate::ParseError;
+use crate::help::Help;
+
+/// An item of [`Builtins`].
+///
+/// [`Builtins`]: struct.Builtins.html
+#[derive(Debug, Clone, Copy)]
+pub enum ParseOutcome<'h, T> {
+    /// an item of the wrapped iterator (usually a key value pair)
+    Arg(T),
+    /// `-help` occurred, print this and exit
+    Help(Help<'h>),
+    /// `-version` occurred, print this and exit
+    Version(&'h str),
+}
+
+/// Handles `-help` and `-version`.
+///
+/// This wraps an iterator (like [`ArgumentIterator`]) and turns
+/// [`ParseError::UnknownKey`]s for `help` and `version` into
+/// [`ParseOutcome::Help`] and [`ParseOutcome::Version`].
+/// So these keys work without being declared, but declared keys take precedence.
+/// The iterator ends after them.
+///
+/// ```
+/// # use miniarg::{Builtins, ParseOutcome};
+/// # use miniarg::help::Help;
+/// let options = ["key"];
+/// let help = Help::new(&[("key", "a key")]);
+/// let args = miniarg::parse("executable -key value -help", &options);
+/// let mut args = Builtins::new(args, help, "tool 1.0");
+/// assert!(matches!(args.next(), Some(Ok(ParseOutcome::Arg((&"key", "value"))))));
+/// let Some(Ok(ParseOutcome::Help(help))) = args.next() else { panic!() };
+/// assert_eq!(help.to_string(), "  -key  a key\n");
+/// assert!(args.next().is_none());
+/// ```
+///
+/// If you compile with `derive`, [`ArgumentIterator::builtins`] creates this
+/// from the documentation comments and the version of your package.
+///
+/// [`ArgumentIterator`]: struct.ArgumentIterator.html
+/// [`ArgumentIterator::builtins`]: struct.ArgumentIterator.html#method.builtins
+/// [`ParseError::UnknownKey`]: enum.ParseError.html#variant.UnknownKey
+/// [`ParseOutcome::Help`]: enum.ParseOutcome.html#variant.Help
+/// [`ParseOutcome::Version`]: enum.ParseOutcome.html#variant.Version
+pub struct Builtins<'h, I> {
+    inner: I,
+    help: Help<'h>,
+    version: &'h str,
+    // whether `-help` or `-version` occurred
+    done: bool,
+}
+
+impl<'h, I> Builtins<'h, I> {
+    /// Handle `-help` and `-version` in `inner`.
+    pub const fn new(inner: I, help: Help<'h>, version: &'h str) -> Self {
+        Self {
+            inner,
+            help,
+            version,
+            done: false,
+        }
+    }
+
+    /// Get the wrapped iterator.
+    pub const fn inner(&self) -> &I {
+        &self.inner
+    }
+}
+
+impl<'a, 'h, I, T, S> Iterator for Builtins<'h, I>
+where
+    I: Iterator<Item = Result<T, ParseError<'a, S>>>,
+    S: AsRef<str>,
+{
+    type Item = Result<ParseOutcome<'h, T>, ParseError<'a, S>>;
+
+    /// Get the next item, `-help` or `-version`.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        Some(match self.inner.next()? {
+            Ok(item) => Ok(ParseOutcome::Arg(item)),
+            Err(ParseError::UnknownKey(key)) if key.as_ref() == "help" => {
+                self.done = true;
+                Ok(ParseOutcome::Help(self.help))
+            }
+            Err(ParseError::UnknownKey(key)) if key.as_ref() == "version" => {
+                self.done = true;
+                Ok(ParseOutcome::Version(self.version))
+            }
+            Err(e) => Err(e),
+        })
+    }
+}
+
+impl<'a, I, T, S> FusedIterator for Builtins<'_, I>
+where
+    I: FusedIterator<Item = Result<T, ParseError<'a, S>>>,
+    S: AsRef<str>,
+{
+}
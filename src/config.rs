@@ -0,0 +1,172 @@
+//! Parses a simple config file format.
+//!
+//! Each line contains a key and a value, separated by whitespace or by `=`.
+//! Empty lines and lines starting with `#` are ignored.
+//! The keys are the same as on the cmdline, just without the leading dash.
+//!
+//! # Usage
+//!
+//! ```
+//! let config = "
+//! ## the defaults
+//! host example.com
+//! port = 8080
+//! ";
+//! let mut items = miniarg::config::parse_config(config, &["host", "port"]);
+//! assert_eq!(items.next(), Some(Ok((&"host", "example.com"))));
+//! assert_eq!(items.next(), Some(Ok((&"port", "8080"))));
+//! assert_eq!(items.next(), None);
+//! ```
+//!
+//! The items are the same as the ones from [`parse`],
+//! so they can be combined with [`merge`]:
+//!
+//! ```
+//! # use miniarg::config::{merge, parse_config};
+//! let options = &["host", "port"];
+//! let file = parse_config("host example.com\nport 8080", options)
+//!     .collect::<Result<Vec<_>, _>>()?;
+//! let cmdline = miniarg::parse("executable -port 80", options)
+//!     .collect::<Result<Vec<_>, _>>()?;
+//! assert_eq!(
+//!     merge(&file, &cmdline).collect::<Vec<_>>(),
+//!     vec![&(&"host", "example.com"), &(&"port", "80")],
+//! );
+//! # Ok::<(), miniarg::ParseError<'static>>(())
+//! ```
+//!
+//! Flags can be declared with [`ConfigIterator::flags`].
+//! Otherwise, nothing of the [`ArgumentIterator`] configuration applies:
+//! keys always take one value (the rest of the line, which may be empty),
+//! and there are no defaults or checks like [`ArgumentIterator::required`].
+//!
+//! [`parse`]: ../fn.parse.html
+//! [`merge`]: fn.merge.html
+//! [`ConfigIterator::flags`]: struct.ConfigIterator.html#method.flags
+//! [`ArgumentIterator`]: ../struct.ArgumentIterator.html
+//! [`ArgumentIterator::required`]: ../struct.ArgumentIterator.html#method.required
+
+use core::iter::FusedIterator;
+use core::str::Lines;
+
+use crate::{ParseError, ToString, find_option};
+
+/// Parse a config file.
+///
+/// You'll get an iterator yielding key value pairs,
+/// see the [module documentation] for details.
+///
+/// [module documentation]: index.html
+pub fn parse_config<'a, 'b, T>(config: &'a str, options: &'b [T]) -> ConfigIterator<'a, 'b, T>
+where
+    T: ToString,
+{
+    ConfigIterator {
+        lines: config.lines(),
+        options,
+        flags: &[],
+    }
+}
+
+/// The iterator returned by [`parse_config`].
+///
+/// [`parse_config`]: fn.parse_config.html
+pub struct ConfigIterator<'a, 'b, T>
+where
+    T: ToString,
+{
+    lines: Lines<'a>,
+    options: &'b [T],
+    flags: &'b [&'b str],
+}
+
+impl<'b, T> ConfigIterator<'_, 'b, T>
+where
+    T: ToString,
+{
+    /// Declare keys that are flags.
+    ///
+    /// A line with just a flag yields the value `"true"`,
+    /// one with just `no-` and a flag yields the value `"false"`
+    /// (like [`ArgumentIterator::flags`] on the cmdline).
+    /// Values that are given explicitly are kept.
+    ///
+    /// ```
+    /// let config = "color\nno-color\ncolor = false";
+    /// let mut items = miniarg::config::parse_config(config, &["color"]).flags(&["color"]);
+    /// assert_eq!(items.next(), Some(Ok((&"color", "true"))));
+    /// assert_eq!(items.next(), Some(Ok((&"color", "false"))));
+    /// assert_eq!(items.next(), Some(Ok((&"color", "false"))));
+    /// assert_eq!(items.next(), None);
+    /// ```
+    ///
+    /// [`ArgumentIterator::flags`]: ../struct.ArgumentIterator.html#method.flags
+    #[must_use]
+    pub fn flags(mut self, keys: &'b [&'b str]) -> Self {
+        self.flags = keys;
+        self
+    }
+}
+
+impl<'a, 'b, T> Iterator for ConfigIterator<'a, 'b, T>
+where
+    T: ToString,
+{
+    type Item = Result<(&'b T, &'a str), ParseError<'a>>;
+
+    /// Get the next key pair or an error.
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = self.lines.next()?.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once(|c: char| c.is_whitespace() || c == '=')
+                .unwrap_or((line, ""));
+            let key = key.trim_end();
+            let value = value.trim_start();
+            let value = value.strip_prefix('=').unwrap_or(value).trim_start();
+            if key.is_empty() {
+                return Some(Err(ParseError::NotAKey(line)));
+            }
+            // flags without a value, negated ones are only considered if there is no such key
+            let (name, value) = match key.strip_prefix("no-") {
+                _ if !value.is_empty() => (key, value),
+                Some(flag)
+                    if find_option(self.options, key).is_none() && self.flags.contains(&flag) =>
+                {
+                    (flag, "false")
+                }
+                _ if self.flags.contains(&key) => (key, "true"),
+                _ => (key, value),
+            };
+            return Some(match find_option(self.options, name) {
+                Some(index) => Ok((&self.options[index], value)),
+                None => Err(ParseError::UnknownKey(key)),
+            });
+        }
+    }
+}
+
+impl<T> FusedIterator for ConfigIterator<'_, '_, T> where T: ToString {}
+
+/// Combine the items of a config file with the ones of the cmdline.
+///
+/// Keys that occur on the cmdline override all of their occurrences in the file.
+/// The remaining items of the file come first, then the items of the cmdline.
+///
+/// See the [module documentation] for an example.
+///
+/// [module documentation]: index.html
+pub fn merge<'c, 'b, T, V>(
+    file: &'c [(&'b T, V)],
+    cmdline: &'c [(&'b T, V)],
+) -> impl Iterator<Item = &'c (&'b T, V)>
+where
+    T: PartialEq,
+{
+    file.iter()
+        .filter(|(key, _)| !cmdline.iter().any(|(other, _)| other == key))
+        .chain(cmdline)
+}
@@ -0,0 +1,64 @@
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+/// A source of environment variables.
+///
+/// This is used by [`ArgumentIterator::env`] to look up the values of keys
+/// that didn't occur on the cmdline.
+///
+/// It's implemented for closures, so that a custom lookup can be used
+/// (for example in tests or without `std`),
+/// and with `std` for [`ProcessEnv`].
+///
+/// ```
+/// # use miniarg::Environment;
+/// let env = |name: &str| (name == "MYAPP_PORT").then_some("80");
+/// assert_eq!(env.var("MYAPP_PORT"), Some("80"));
+/// assert_eq!(env.var("MYAPP_HOST"), None);
+/// ```
+///
+/// [`ArgumentIterator::env`]: struct.ArgumentIterator.html#method.env
+/// [`ProcessEnv`]: struct.ProcessEnv.html
+pub trait Environment<'a> {
+    /// Get the value of the environment variable `name`, if it's set.
+    fn var(&self, name: &str) -> Option<&'a str>;
+}
+
+impl<'a, F> Environment<'a> for F
+where
+    F: Fn(&str) -> Option<&'a str>,
+{
+    fn var(&self, name: &str) -> Option<&'a str> {
+        self(name)
+    }
+}
+
+/// The environment variables of the current process.
+///
+/// They are collected once (on the first lookup) and then kept until the end of the program,
+/// so the returned values are `'static`.
+/// Variables that are not valid unicode are ignored.
+///
+/// This is the default [`Environment`] with the `std` feature.
+///
+/// [`Environment`]: trait.Environment.html
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ProcessEnv;
+
+#[cfg(feature = "std")]
+impl Environment<'_> for ProcessEnv {
+    fn var(&self, name: &str) -> Option<&'static str> {
+        static VARS: OnceLock<HashMap<String, String>> = OnceLock::new();
+        let vars = VARS.get_or_init(|| {
+            std::env::vars_os()
+                .filter_map(|(name, value)| {
+                    Some((name.into_string().ok()?, value.into_string().ok()?))
+                })
+                .collect()
+        });
+        vars.get(name).map(String::as_str)
+    }
+}
@@ -0,0 +1,204 @@
+//! Formats help texts.
+//!
+//! The descriptions of the keys are aligned into a column and wrapped
+//! to a fixed width, so that they look the same on every terminal
+//! (unlike tabs, whose stops differ between them).
+//!
+//! # Usage
+//!
+//! ```
+//! # use miniarg::help::Help;
+//! let options = [
+//!     ("port", "the serial port"),
+//!     ("baud", "the baud rate, which has to be supported by the device [default: 115200]"),
+//! ];
+//! let help = Help::new(&options).description("Talks to a device.").width(50);
+//! assert_eq!(
+//!     help.to_string(),
+//!     "\
+//! Talks to a device.
+//!
+//!   -port  the serial port
+//!   -baud  the baud rate, which has to be supported
+//!          by the device [default: 115200]
+//! ",
+//! );
+//! ```
+//!
+//! The keys are given without the leading dash, like for [`parse`].
+//! Newlines in descriptions start a new paragraph.
+//!
+//! [`Help`] implements [`Display`], so it can be written to anything
+//! implementing [`fmt::Write`] without an allocation.
+//! If you compile with `derive`, [`Key::help`] creates it
+//! from the documentation comments.
+//!
+//! [`parse`]: ../fn.parse.html
+//! [`Help`]: struct.Help.html
+//! [`Display`]: https://doc.rust-lang.org/core/fmt/trait.Display.html
+//! [`fmt::Write`]: https://doc.rust-lang.org/core/fmt/trait.Write.html
+//! [`Key::help`]: ../trait.Key.html#method.help
+
+use core::fmt;
+
+/// The indentation of the keys.
+const INDENT: usize = 2;
+/// The space between the keys and their descriptions.
+const GAP: usize = 2;
+
+/// A help text.
+///
+/// See the [module documentation] for more details.
+///
+/// [module documentation]: index.html
+#[derive(Debug, Clone, Copy)]
+pub struct Help<'a> {
+    description: &'a str,
+    options: &'a [(&'a str, &'a str)],
+    width: usize,
+}
+
+impl<'a> Help<'a> {
+    /// Create a help text for the keys and their descriptions in `options`.
+    ///
+    /// The text is wrapped at 80 chars.
+    #[must_use]
+    pub const fn new(options: &'a [(&'a str, &'a str)]) -> Self {
+        Self {
+            description: "",
+            options,
+            width: 80,
+        }
+    }
+
+    /// Put a description of the program in front of the keys.
+    #[must_use]
+    pub const fn description(mut self, description: &'a str) -> Self {
+        self.description = description;
+        self
+    }
+
+    /// Wrap the text at `width` chars.
+    ///
+    /// Words that are longer than the available space aren't split.
+    /// Keys that are longer than half of the width get their description
+    /// on the next line.
+    #[must_use]
+    pub const fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Get the column the descriptions start at.
+    fn column(&self) -> usize {
+        let longest = self
+            .options
+            .iter()
+            .map(|(name, _)| INDENT + 1 + name.chars().count() + GAP)
+            .filter(|len| *len <= self.width / 2)
+            .max();
+        longest.unwrap_or(self.width / 2)
+    }
+
+    /// Write the help text to `f`.
+    ///
+    /// # Errors
+    ///
+    /// This only fails if writing to `f` does.
+    pub fn write<W>(&self, f: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        if !self.description.is_empty() {
+            wrap(self.description, 0, self.width, f)?;
+            f.write_char('\n')?;
+        }
+        let column = self.column();
+        for (name, description) in self.options {
+            write!(f, "{:INDENT$}-{name}", "")?;
+            if description.is_empty() {
+                f.write_char('\n')?;
+                continue;
+            }
+            let len = INDENT + 1 + name.chars().count();
+            if len + GAP > column {
+                write!(f, "\n{:column$}", "")?;
+            } else {
+                write!(f, "{:1$}", "", column - len)?;
+            }
+            wrap(description, column, self.width, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Help<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f)
+    }
+}
+
+/// Write `text` wrapped at `width` and indented by `indent`, ending with a newline.
+///
+/// The first line is expected to be indented already.
+fn wrap<W>(text: &str, indent: usize, width: usize, f: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    for (index, paragraph) in text.split('\n').enumerate() {
+        if index > 0 {
+            write!(f, "\n{:indent$}", "")?;
+        }
+        let mut len = indent;
+        for word in paragraph.split_whitespace() {
+            let word_len = word.chars().count();
+            if len > indent {
+                if len + 1 + word_len > width {
+                    write!(f, "\n{:indent$}", "")?;
+                    len = indent;
+                } else {
+                    f.write_char(' ')?;
+                    len += 1;
+                }
+            }
+            f.write_str(word)?;
+            len += word_len;
+        }
+    }
+    f.write_char('\n')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_buffer::Buffer;
+
+    macro_rules! test {
+        ($test:ident: $help:expr => $text:expr) => {
+            #[test]
+            fn $test() {
+                let mut buf = Buffer::new();
+                $help.write(&mut buf).unwrap();
+                assert_eq!(buf.as_str(), $text);
+            }
+        };
+    }
+
+    test!(empty: Help::new(&[]) => "");
+    test!(aligned: Help::new(&[("a", "first"), ("abc", "second")])
+        => "  -a    first\n  -abc  second\n");
+    test!(undocumented: Help::new(&[("a", ""), ("abc", "second")])
+        => "  -a\n  -abc  second\n");
+    test!(wrapped: Help::new(&[("a", "one two three four")]).width(14)
+        => "  -a  one two\n      three\n      four\n");
+    test!(long_word: Help::new(&[("a", "abcdefghij k")]).width(12)
+        => "  -a  abcdefghij\n      k\n");
+    test!(paragraphs: Help::new(&[("a", "one\ntwo")])
+        => "  -a  one\n      two\n");
+    test!(long_key: Help::new(&[("a", "first"), ("abcdefgh", "second")]).width(20)
+        => "  -a  first\n  -abcdefgh\n      second\n");
+    test!(non_ascii: Help::new(&[("köy", "äöü äöü äöü")]).width(16)
+        => "  -köy  äöü äöü\n        äöü\n");
+    test!(description: Help::new(&[("a", "first")]).description("Does\nthings.")
+        => "Does\nthings.\n\n  -a  first\n");
+}
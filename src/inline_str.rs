@@ -0,0 +1,94 @@
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+/// A string that is stored inline in a fixed-size buffer.
+///
+/// This is used by [`InlineParseError`] to copy the offending token
+/// out of the cmdline without an allocation.
+/// Strings longer than `N` bytes are truncated (at a char boundary).
+///
+/// [`InlineParseError`]: type.InlineParseError.html
+#[derive(Clone, Copy)]
+pub struct InlineStr<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+    truncated: bool,
+}
+
+impl<const N: usize> InlineStr<N> {
+    /// Copy `s` into a new buffer, truncating it if needed.
+    #[must_use]
+    pub fn new(s: &str) -> Self {
+        let mut len = s.len().min(N);
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+        let mut buf = [0; N];
+        buf[..len].copy_from_slice(&s.as_bytes()[..len]);
+        Self {
+            buf,
+            len,
+            truncated: len < s.len(),
+        }
+    }
+
+    /// Get the (possibly truncated) string.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        // this was copied from a `str` at a char boundary
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or_default()
+    }
+
+    /// Whether the original string was longer than `N` bytes.
+    #[must_use]
+    pub const fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+impl<const N: usize> AsRef<str> for InlineStr<N> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> From<&str> for InlineStr<N> {
+    fn from(s: &str) -> Self {
+        Self::new(s)
+    }
+}
+
+impl<const N: usize> fmt::Display for InlineStr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())?;
+        if self.truncated {
+            f.write_str("...")?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> fmt::Debug for InlineStr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)?;
+        if self.truncated {
+            f.write_str("...")?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> PartialEq for InlineStr<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str() && self.truncated == other.truncated
+    }
+}
+
+impl<const N: usize> Eq for InlineStr<N> {}
+
+impl<const N: usize> Hash for InlineStr<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+        self.truncated.hash(state);
+    }
+}
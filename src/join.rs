@@ -0,0 +1,216 @@
+//! Joins multiple args into a cmdline.
+//!
+//! This is the inverse of [`split_args`]: The resulting cmdline is split back
+//! into exactly the original args by [`SplitArgs`] (and thus by [`parse`]).
+//!
+//! # Usage
+//!
+//! ```
+//! # use miniarg::join::join;
+//! # use miniarg::split_args::SplitArgs;
+//! let args = ["executable", "param1", "param2, but with spaces", "it's"];
+//! let mut cmdline = String::new();
+//! join(args, &mut cmdline)?;
+//! assert_eq!(cmdline, "executable param1 'param2, but with spaces' it's");
+//! assert!(SplitArgs::new(&cmdline).eq(args));
+//! # Ok::<(), miniarg::join::JoinError>(())
+//! ```
+//!
+//! Args are only quoted if they need to be.
+//! Single quotes are preferred, double quotes are used if the arg contains a single quote.
+//! As [`SplitArgs`] doesn't support escaping, args that need to be quoted
+//! and contain both kinds of quotes can't be joined.
+//!
+//! [`split_args`]: ../split_args/index.html
+//! [`SplitArgs`]: ../split_args/struct.SplitArgs.html
+//! [`parse`]: ../fn.parse.html
+
+use core::fmt;
+
+use crate::parse::{Char, Quote};
+
+/// Joins multiple args into a cmdline and writes it to `f`.
+///
+/// The args are separated by a single space.
+///
+/// See the [module documentation] for more details.
+///
+/// [module documentation]: index.html
+pub fn join<I, W>(args: I, f: &mut W) -> Result<(), JoinError>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+    W: fmt::Write,
+{
+    for (index, arg) in args.into_iter().enumerate() {
+        if index > 0 {
+            f.write_char(' ')?;
+        }
+        quote(arg.as_ref(), f).map_err(|e| match e {
+            JoinError::Unquotable { .. } => JoinError::Unquotable { index },
+            e => e,
+        })?;
+    }
+    Ok(())
+}
+
+/// Quotes a single arg (if needed) and writes it to `f`.
+///
+/// Errors refer to the arg as index 0.
+///
+/// See the [module documentation] for more details.
+///
+/// [module documentation]: index.html
+pub fn quote<W>(arg: &str, f: &mut W) -> Result<(), JoinError>
+where
+    W: fmt::Write,
+{
+    let mut chars = arg.chars().map(Char::from);
+    let needs_quotes = match chars.next() {
+        // an empty arg would just vanish
+        None | Some(Char::Whitespace | Char::Quote(_)) => true,
+        // quotes are only special at the start of an arg
+        Some(Char::Letter(_)) => chars.any(|c| c == Char::Whitespace),
+    };
+    if !needs_quotes {
+        f.write_str(arg)?;
+        return Ok(());
+    }
+    let quote = if !arg.contains('\'') {
+        Quote::Single
+    } else if !arg.contains('"') {
+        Quote::Double
+    } else {
+        return Err(JoinError::Unquotable { index: 0 });
+    };
+    let quote = match quote {
+        Quote::Single => '\'',
+        Quote::Double => '"',
+    };
+    f.write_char(quote)?;
+    f.write_str(arg)?;
+    f.write_char(quote)?;
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[non_exhaustive]
+/// Errors occurred during joining the args.
+pub enum JoinError {
+    /// the arg at `index` needs to be quoted, but contains both kinds of quotes
+    Unquotable { index: usize },
+    /// writing to the output failed
+    Write,
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Self::Unquotable { index } => {
+                write!(f, "the arg at index {index} can't be quoted")
+            }
+            Self::Write => write!(f, "failed to write the cmdline"),
+        }
+    }
+}
+impl crate::Error for JoinError {}
+
+impl From<fmt::Error> for JoinError {
+    fn from(_: fmt::Error) -> Self {
+        Self::Write
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::split_args::SplitArgs;
+    use crate::test_buffer::Buffer;
+
+    macro_rules! test {
+        ($test:ident: [ $($arg:expr),* ] => $cmdline:expr) => {
+            #[test]
+            fn $test() {
+                let args: &[&str] = &[$($arg),*];
+                let mut buf = Buffer::new();
+                join(args, &mut buf).unwrap();
+                assert_eq!(buf.as_str(), $cmdline);
+                assert!(SplitArgs::new(buf.as_str()).eq(args.iter().copied()));
+            }
+        };
+    }
+
+    test!(basic: ["string"] => "string");
+    test!(two: ["string1", "string2"] => "string1 string2");
+    test!(empty: ["1", "", "3"] => "1 '' 3");
+    test!(spaces: ["string1", "string2 string3"] => "string1 'string2 string3'");
+    test!(single_quote: ["it's a test"] => "\"it's a test\"");
+    test!(inner_quotes: ["te'st\"s"] => "te'st\"s");
+    test!(leading_single_quote: ["'test"] => "\"'test\"");
+    test!(leading_double_quote: ["\"test"] => "'\"test'");
+    test!(other_whitespace: ["1\t2", "3\n4"] => "'1\t2' '3\n4'");
+    test!(non_ascii: ["rusty 🦀", "party🎉time"] => "'rusty 🦀' party🎉time");
+
+    #[test]
+    fn unquotable() {
+        let mut buf = Buffer::new();
+        assert_eq!(
+            join(["fine", "it's \"not\" fine"], &mut buf),
+            Err(JoinError::Unquotable { index: 1 })
+        );
+    }
+
+    #[test]
+    fn too_long() {
+        let arg = core::str::from_utf8(&[b'a'; 300]).unwrap();
+        assert_eq!(join([arg], &mut Buffer::new()), Err(JoinError::Write));
+    }
+
+    /// A simple xorshift generator, so that the property test is reproducible.
+    struct Rng(u32);
+
+    impl Rng {
+        fn next(&mut self) -> u32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 17;
+            self.0 ^= self.0 << 5;
+            self.0
+        }
+    }
+
+    #[test]
+    /// Joining random args and splitting them again should result in the same args.
+    fn round_trip() {
+        const ALPHABET: &[char] = &['a', 'b', 'ä', '🦀', ' ', '\t', '\n', '\'', '"', '-'];
+        let mut rng = Rng(0x2545_f491);
+        for _ in 0..10_000 {
+            let mut storage = [[0u8; 32]; 4];
+            let mut lens = [0usize; 4];
+            let count = rng.next() as usize % 5;
+            for (arg, len) in storage.iter_mut().zip(lens.iter_mut()).take(count) {
+                for _ in 0..rng.next() % 6 {
+                    let c = ALPHABET[rng.next() as usize % ALPHABET.len()];
+                    *len += c.encode_utf8(&mut arg[*len..]).len();
+                }
+            }
+            let args = storage
+                .iter()
+                .zip(lens)
+                .take(count)
+                .map(|(arg, len)| core::str::from_utf8(&arg[..len]).unwrap());
+            let mut buf = Buffer::new();
+            match join(args.clone(), &mut buf) {
+                Ok(()) => assert!(
+                    SplitArgs::new(buf.as_str()).eq(args.clone()),
+                    "{:?} doesn't split into the original args",
+                    buf.as_str(),
+                ),
+                Err(JoinError::Unquotable { index }) => {
+                    let arg = args.clone().nth(index).unwrap();
+                    assert!(arg.contains('\'') && arg.contains('"'));
+                }
+                Err(e) => panic!("unexpected error {e:?}"),
+            }
+        }
+    }
+}
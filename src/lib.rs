@@ -4,11 +4,12 @@
 //! That means:
 //!
 //! * values are strings
-//! * keys start with a single dash
-//! * keys can occur multiple times
+//! * keys start with a single dash (unless configured, see [`ArgumentIterator::prefixes`])
+//! * keys can occur multiple times (unless limited, see [`ArgumentIterator::occurrences`])
 //!
 //! The last parameter can also be just a key without a value.
 //! (This can be useful for `-help`.)
+//! If you'd rather get an error, use [`ArgumentIterator::strict_values`].
 //!
 //! # Usage
 //!
@@ -80,34 +81,131 @@
 //! # Ok::<(), miniarg::ParseError<'static>>(())
 //! ```
 //!
-//! In this case a help text is generated from the documentation comments on your enum kinds,
-//! `help_text()` retrieves it.
+//! In this case a help text is generated from the documentation comments on your enum
+//! and its kinds, `help_text()` retrieves it.
+//! `help()` aligns it into columns and wraps it instead, see [`help`].
+//! To handle `-help` and `-version` without declaring them, see [`Builtins`].
+//! `man_page()` renders a man page from the same information, see [`man`].
+//!
+//! For tools with subcommands (like `fwtool flash -port x`), derive [`Subcommand`]
+//! for an enum whose kinds wrap enums deriving `Key`.
 //!
 //! The code never panics, but the returned iterator will contain [`ParseError`]s
 //! if anything goes wrong.
 //!
+//! The errors borrow from the cmdline. If they need to outlive it (for example to return
+//! them from `main`), [`into_owned`] copies them (this needs `std` or `alloc`):
+//! ```
+//! # #[cfg(feature = "std")]
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let cmdline = String::from("executable -key value");
+//!     let args = miniarg::parse(&cmdline, &["key"])
+//!         .collect::<Result<Vec<_>, _>>()
+//!         .map_err(miniarg::ParseError::into_owned)?;
+//!     Ok(())
+//! }
+//! # #[cfg(not(feature = "std"))]
+//! # fn main() {}
+//! ```
+//! Without `alloc`, [`into_inline`] copies them into a fixed-size buffer instead.
+//!
+//! If you compile with `std` or `alloc`, the iterator can also yield `String`s.
+//! In this case you'll get owned values (and errors), so they can outlive the arguments:
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! let iter = vec![String::from("executable"), String::from("-key"), String::from("value")];
+//! let mut args = miniarg::parse_from_iter(iter.into_iter(), &["key"]);
+//! assert_eq!(args.next(), Some(Ok((&"key", String::from("value")))));
+//! assert_eq!(args.next(), None);
+//! # }
+//! ```
+//!
+//! If you compile with `std`, you can also parse the arguments of the current process,
+//! without collecting them first:
+//! ```no_run
+//! # #[cfg(feature = "std")] {
+//! let mut args = miniarg::parse_env(&["key"]);
+//! # }
+//! # Ok::<(), miniarg::ParseError<'static>>(())
+//! ```
+//! As the process arguments live until the end of the program,
+//! the returned items (and errors) are `'static`.
+//!
+//! Unterminated quotes in the cmdline are accepted and just end at the end of the cmdline.
+//! If you'd rather get an error, use [`parse_strict`]:
+//! ```
+//! let cmdline = "executable -key \"value";
+//! let mut args = miniarg::parse_strict(&cmdline, &["key"]);
+//! assert_eq!(
+//!     args.next(),
+//!     Some(Err(miniarg::ParseError::UnterminatedQuote { start: 16 }))
+//! );
+//! ```
+//!
 //! You might also want to take a look at the [`split_args`] module for lower level access.
+//! The [`join`] module does the opposite and creates a cmdline from args.
+//! The [`response_files`] module expands response files (`@file`) in the args.
+//! The [`config`] module parses config files with the same keys.
 //!
+//! [`parse_strict`]: fn.parse_strict.html
+//! [`ArgumentIterator::occurrences`]: struct.ArgumentIterator.html#method.occurrences
+//! [`ArgumentIterator::prefixes`]: struct.ArgumentIterator.html#method.prefixes
+//! [`ArgumentIterator::strict_values`]: struct.ArgumentIterator.html#method.strict_values
 //! [`ToString`]: https://doc.rust-lang.org/nightly/alloc/string/trait.ToString.html
 //! [`ParseError`]: enum.ParseError.html
+//! [`into_owned`]: enum.ParseError.html#method.into_owned
+//! [`into_inline`]: enum.ParseError.html#method.into_inline
 //! [`split_args`]: split_args/index.html
+//! [`join`]: join/index.html
+//! [`Subcommand`]: trait.Subcommand.html
+//! [`config`]: config/index.html
+//! [`response_files`]: response_files/index.html
+//! [`help`]: help/index.html
+//! [`Builtins`]: struct.Builtins.html
+//! [`man`]: man/index.html
 #![doc(html_root_url = "https://docs.rs/miniarg/0.5.0")]
 #![cfg_attr(not(feature = "std"), no_std)]
 #[cfg(feature = "alloc")]
 extern crate alloc;
 #[cfg(feature = "alloc")]
 use alloc::string::{String, ToString};
+use core::convert::Infallible;
 use core::fmt;
-use core::iter::Skip;
+use core::marker::PhantomData;
 #[cfg(feature = "std")]
 use std::error::Error;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
 
 use cfg_if::cfg_if;
 
+mod arity;
+mod builtins;
+mod environment;
+mod inline_str;
+mod occurrences;
 mod parse;
+#[cfg(feature = "derive")]
+mod subcommand;
+#[cfg(test)]
+mod test_buffer;
 
+pub use arity::{Arity, Grouped};
+pub use builtins::{Builtins, ParseOutcome};
+pub use environment::Environment;
+#[cfg(feature = "std")]
+pub use environment::ProcessEnv;
+pub use inline_str::InlineStr;
+pub use occurrences::Occurrences;
+#[cfg(feature = "derive")]
+pub use subcommand::{Subcommand, SubcommandIterator};
+pub mod config;
+pub mod help;
+pub mod join;
+pub mod man;
+pub mod response_files;
 pub mod split_args;
-use split_args::SplitArgs;
+use split_args::{SplitArgs, SplitError, TrySplitArgs};
 
 // This is a bit of a hack to allow building without std and without alloc.
 #[cfg(not(feature = "alloc"))]
@@ -137,23 +235,206 @@ where
     ArgumentIterator::<'a, 'b, T, SplitArgs>::new(args, options)
 }
 
+/// Parse the command line, but report unterminated quotes.
+///
+/// It's like [`parse`] but splits the cmdline using [`TrySplitArgs`],
+/// so a quote that is never closed results in [`ParseError::UnterminatedQuote`].
+///
+/// [`parse`]: fn.parse.html
+/// [`TrySplitArgs`]: split_args/struct.TrySplitArgs.html
+/// [`ParseError::UnterminatedQuote`]: enum.ParseError.html#variant.UnterminatedQuote
+pub fn parse_strict<'a, 'b, T>(
+    cmdline: &'a str,
+    options: &'b [T],
+) -> ArgumentIterator<'a, 'b, T, TrySplitArgs<'a>>
+where
+    T: ToString,
+{
+    let args = TrySplitArgs::new(cmdline);
+    ArgumentIterator::<'a, 'b, T, TrySplitArgs>::new(args, options)
+}
+
 /// Parse from a custom iterator.
 ///
 /// It's like [`parse`] but instead of taking a string and splitting it using [`SplitArgs`]
 /// it takes the options from a custom iterator.
 ///
+/// The iterator can either yield `&str`s or `Result`s,
+/// see [`Argument`] for details.
+///
 /// See the main crate documentation for more details and examples.
 ///
 /// [`parse`]: fn.parse.html
 /// [`SplitArgs`]: split_args/struct.SplitArgs.html
+/// [`Argument`]: trait.Argument.html
 pub fn parse_from_iter<'a, 'b, T, S>(args: S, options: &'b [T]) -> ArgumentIterator<'a, 'b, T, S>
 where
     T: ToString,
-    S: Iterator<Item = &'a str>,
+    S: Iterator,
+    S::Item: Argument<'a>,
 {
     ArgumentIterator::<'a, 'b, T, S>::new(args, options)
 }
 
+/// Parse the arguments of the current process.
+///
+/// It's like [`parse_from_iter`] but takes the arguments from [`std::env::args_os`].
+/// They are collected once and then kept until the end of the program,
+/// so the returned items are `'static`.
+///
+/// Arguments that are not valid unicode result in [`ParseError::NotUnicode`].
+///
+/// This requires the `std` feature.
+///
+/// [`parse_from_iter`]: fn.parse_from_iter.html
+/// [`std::env::args_os`]: https://doc.rust-lang.org/std/env/fn.args_os.html
+/// [`ParseError::NotUnicode`]: enum.ParseError.html#variant.NotUnicode
+#[cfg(feature = "std")]
+pub fn parse_env<T>(options: &[T]) -> ArgumentIterator<'static, '_, T, EnvArgs>
+where
+    T: ToString,
+{
+    ArgumentIterator::<'static, '_, T, EnvArgs>::new(EnvArgs::new(), options)
+}
+
+/// The arguments of the current process.
+///
+/// This is used by [`parse_env`], see there for details.
+///
+/// [`parse_env`]: fn.parse_env.html
+#[cfg(feature = "std")]
+pub struct EnvArgs {
+    args: core::slice::Iter<'static, Result<String, String>>,
+}
+
+#[cfg(feature = "std")]
+impl EnvArgs {
+    /// Get the arguments of the current process.
+    #[must_use]
+    pub fn new() -> Self {
+        static ARGS: OnceLock<Vec<Result<String, String>>> = OnceLock::new();
+        let args = ARGS.get_or_init(|| {
+            std::env::args_os()
+                .map(|arg| {
+                    arg.into_string()
+                        .map_err(|arg| arg.to_string_lossy().into_owned())
+                })
+                .collect()
+        });
+        Self { args: args.iter() }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for EnvArgs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Iterator for EnvArgs {
+    type Item = Result<&'static str, ParseError<'static>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.args.next().map(|arg| match arg {
+            Ok(arg) => Ok(arg.as_str()),
+            Err(lossy) => Err(ParseError::NotUnicode(lossy.as_str())),
+        })
+    }
+}
+
+/// An item that can be passed to [`parse_from_iter`].
+///
+/// This is implemented for `&str` and for `Result`s of `&str`,
+/// so that errors of the underlying iterator (for example from [`TrySplitArgs`])
+/// can be passed through as [`ParseError`]s.
+///
+/// With `alloc`, it's also implemented for `String` (and `Result`s of it).
+/// In this case, the values are `String`s and the errors contain `String`s, too,
+/// so they can outlive the source of the arguments.
+///
+/// [`parse_from_iter`]: fn.parse_from_iter.html
+/// [`TrySplitArgs`]: split_args/struct.TrySplitArgs.html
+/// [`ParseError`]: enum.ParseError.html
+pub trait Argument<'a> {
+    /// The type of the values (and of the strings contained in errors).
+    type Str: ArgStr<'a>;
+
+    /// Get the argument or the error that occurred while retrieving it.
+    fn into_argument(self) -> Result<Self::Str, ParseError<'a, Self::Str>>;
+}
+
+impl<'a> Argument<'a> for &'a str {
+    type Str = &'a str;
+
+    fn into_argument(self) -> Result<&'a str, ParseError<'a>> {
+        Ok(self)
+    }
+}
+
+impl<'a, E> Argument<'a> for Result<&'a str, E>
+where
+    E: Into<ParseError<'a>>,
+{
+    type Str = &'a str;
+
+    fn into_argument(self) -> Result<&'a str, ParseError<'a>> {
+        self.map_err(Into::into)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Argument<'a> for String {
+    type Str = String;
+
+    fn into_argument(self) -> Result<String, ParseError<'a, String>> {
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, E> Argument<'a> for Result<String, E>
+where
+    E: Into<ParseError<'a, String>>,
+{
+    type Str = String;
+
+    fn into_argument(self) -> Result<String, ParseError<'a, String>> {
+        self.map_err(Into::into)
+    }
+}
+
+/// A string type for values and errors.
+///
+/// This is implemented for `&str` and (with `alloc`) for `String`.
+/// See [`Argument`] for details.
+///
+/// [`Argument`]: trait.Argument.html
+pub trait ArgStr<'a>: AsRef<str> + From<&'a str> {
+    /// Remove `prefix` from the start of the string.
+    ///
+    /// If the string doesn't start with `prefix`, it's returned unchanged.
+    #[must_use]
+    fn without_prefix(self, prefix: &str) -> Self;
+}
+
+impl<'a> ArgStr<'a> for &'a str {
+    fn without_prefix(self, prefix: &str) -> Self {
+        self.strip_prefix(prefix).unwrap_or(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ArgStr<'_> for String {
+    fn without_prefix(mut self, prefix: &str) -> Self {
+        if self.starts_with(prefix) {
+            self.drain(..prefix.len());
+        }
+        self
+    }
+}
+
 /// The iterator returned by [`parse`] and [`parse_from_iter`].
 ///
 /// [`parse`]: fn.parse.html
@@ -161,66 +442,927 @@ where
 pub struct ArgumentIterator<'a, 'b, T, S>
 where
     T: ToString,
-    S: Iterator<Item = &'a str>,
+    S: Iterator,
+    S::Item: Argument<'a>,
 {
-    args: Skip<S>,
+    args: S,
+    // argv[0]
+    program_name: Option<ArgResult<'a, S::Item>>,
     options: &'b [T],
-    last: Option<&'b T>,
+    // what the next argument is expected to be
+    expect: Expect<'b, T, <S::Item as Argument<'a>>::Str>,
+    // an argument that ended the values of the last key and still has to be parsed
+    pending: Option<ArgResult<'a, S::Item>>,
+    required: &'b [&'a str],
+    occurrences: &'b [(&'a str, Occurrences)],
+    conflicts: &'b [(&'a str, &'a str)],
+    requires: &'b [(&'a str, &'a str)],
+    defaults: &'b [(&'a str, &'a str)],
+    env: &'b [(&'a str, &'a str)],
+    flags: &'b [&'a str],
+    arities: &'b [(&'a str, Arity)],
+    // whether a key without a value at the end is an error
+    strict_values: bool,
+    allow_hyphen_values: &'b [&'a str],
+    prefixes: &'b [&'a str],
+    deprecated: &'b [(&'a str, &'a str)],
+    env_source: Option<&'b dyn Environment<'a>>,
+    // which options occurred (one bit per index)
+    seen: u128,
+    // how often the keys in `occurrences` occurred (one counter per entry)
+    counts: [usize; MAX_OCCURRENCES],
+    // how many keys were accepted (including the ones from defaults and the environment)
+    keys_seen: usize,
+    // the first key whose constraints can't be checked
+    untracked: Option<&'a str>,
+    // how many of the checks after the last argument are done
+    // (`None` while there are still arguments left)
+    finished: Option<usize>,
+    // the lifetime of the arguments
+    phantom: PhantomData<&'a str>,
+}
+
+/// An argument or an error of the underlying iterator.
+type ArgResult<'a, A> = Result<<A as Argument<'a>>::Str, ParseError<'a, <A as Argument<'a>>::Str>>;
+
+/// What the next argument is expected to be.
+enum Expect<'b, T, S> {
+    /// a key
+    Key,
+    /// a value of `option` (`key` is the name it occurred with)
+    Values {
+        option: &'b T,
+        key: S,
+        arity: Arity,
+        got: usize,
+    },
+    /// a value of a rejected key, which is skipped
+    Skip {
+        arity: Arity,
+        got: usize,
+        allow_hyphen_values: bool,
+    },
 }
 
 impl<'a, 'b, T, S> ArgumentIterator<'a, 'b, T, S>
 where
     T: ToString,
-    S: Iterator<Item = &'a str>,
+    S: Iterator,
+    S::Item: Argument<'a>,
 {
-    fn new(args: S, options: &'b [T]) -> Self {
-        // skip argv[0]
+    fn new(mut args: S, options: &'b [T]) -> Self {
+        // argv[0] is the name of the program,
+        // but errors have to be reported even there
+        let (program_name, pending) = match args.next().map(Argument::into_argument) {
+            Some(Err(e)) => (None, Some(Err(e))),
+            program_name => (program_name, None),
+        };
         ArgumentIterator {
-            args: args.skip(1),
+            args,
+            program_name,
             options,
-            last: None,
+            expect: Expect::Key,
+            pending,
+            required: &[],
+            occurrences: &[],
+            conflicts: &[],
+            requires: &[],
+            defaults: &[],
+            env: &[],
+            flags: &[],
+            arities: &[],
+            strict_values: false,
+            allow_hyphen_values: &[],
+            prefixes: &["-"],
+            deprecated: &[],
+            #[cfg(feature = "std")]
+            env_source: Some(&ProcessEnv),
+            #[cfg(not(feature = "std"))]
+            env_source: None,
+            seen: 0,
+            counts: [0; MAX_OCCURRENCES],
+            keys_seen: 0,
+            untracked: None,
+            finished: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Parse the first argument, too.
+    ///
+    /// By default, the first argument is the name of the program (see [`program_name`]),
+    /// so it's not parsed. This is wrong if there is none, for example for kernel cmdlines.
+    ///
+    /// This has to be called before iterating.
+    ///
+    /// ```
+    /// let mut args = miniarg::parse("-key value", &["key"]).without_program_name();
+    /// assert_eq!(args.program_name(), None);
+    /// assert_eq!(args.next(), Some(Ok((&"key", "value"))));
+    /// assert_eq!(args.next(), None);
+    /// ```
+    ///
+    /// [`program_name`]: #method.program_name
+    #[must_use]
+    pub fn without_program_name(mut self) -> Self {
+        if let Some(arg) = self.program_name.take() {
+            self.pending = Some(arg);
+        }
+        self
+    }
+
+    /// Get the name of the program (the first argument), if there is one.
+    ///
+    /// ```
+    /// let args = miniarg::parse("executable -key value", &["key"]);
+    /// assert_eq!(args.program_name(), Some("executable"));
+    /// ```
+    #[must_use]
+    pub fn program_name(&self) -> Option<&str> {
+        self.program_name.as_ref()?.as_ref().ok().map(AsRef::as_ref)
+    }
+
+    /// Declare keys that have to occur.
+    ///
+    /// After all arguments have been parsed, the iterator yields a
+    /// [`ParseError::MissingKey`] for each of them that didn't occur.
+    ///
+    /// Only the first 128 options can be tracked,
+    /// for later ones see [`ParseError::UntrackedKey`].
+    ///
+    /// ```
+    /// # use miniarg::ParseError;
+    /// let cmdline = "executable -foo value";
+    /// let mut args = miniarg::parse(&cmdline, &["foo", "bar"]).required(&["bar"]);
+    /// assert_eq!(args.next(), Some(Ok((&"foo", "value"))));
+    /// assert_eq!(args.next(), Some(Err(ParseError::MissingKey("bar"))));
+    /// assert_eq!(args.next(), None);
+    /// ```
+    ///
+    /// [`ParseError::MissingKey`]: enum.ParseError.html#variant.MissingKey
+    /// [`ParseError::UntrackedKey`]: enum.ParseError.html#variant.UntrackedKey
+    #[must_use]
+    pub fn required(mut self, keys: &'b [&'a str]) -> Self {
+        keys.iter().for_each(|key| self.track(key));
+        self.required = keys;
+        self
+    }
+
+    /// Limit how often keys may occur.
+    ///
+    /// If a key occurs more often than allowed, the iterator yields a
+    /// [`ParseError::DuplicateKey`] (instead of the key and its value).
+    /// If it occurs less often than required, this is reported after all arguments
+    /// have been parsed: as [`ParseError::MissingKey`] if it didn't occur at all,
+    /// and as [`ParseError::TooFewOccurrences`] otherwise.
+    ///
+    /// Only 32 keys can have limits, for more see [`ParseError::UntrackedKey`].
+    ///
+    /// ```
+    /// # use miniarg::{Occurrences, ParseError};
+    /// let cmdline = "executable -output a -output b";
+    /// let mut args = miniarg::parse(&cmdline, &["output"])
+    ///     .occurrences(&[("output", Occurrences::AT_MOST_ONCE)]);
+    /// assert_eq!(args.next(), Some(Ok((&"output", "a"))));
+    /// assert_eq!(args.next(), Some(Err(ParseError::DuplicateKey("output"))));
+    /// assert_eq!(args.next(), None);
+    /// ```
+    ///
+    /// [`ParseError::DuplicateKey`]: enum.ParseError.html#variant.DuplicateKey
+    /// [`ParseError::MissingKey`]: enum.ParseError.html#variant.MissingKey
+    /// [`ParseError::TooFewOccurrences`]: enum.ParseError.html#variant.TooFewOccurrences
+    /// [`ParseError::UntrackedKey`]: enum.ParseError.html#variant.UntrackedKey
+    #[must_use]
+    pub fn occurrences(mut self, limits: &'b [(&'a str, Occurrences)]) -> Self {
+        if let Some(&(key, _)) = limits.get(MAX_OCCURRENCES) {
+            self.untracked.get_or_insert(key);
+        }
+        self.occurrences = limits;
+        self
+    }
+
+    /// Declare pairs of keys that can't be used together.
+    ///
+    /// After all arguments have been parsed, the iterator yields a
+    /// [`ParseError::Conflict`] for each pair where both keys occurred.
+    ///
+    /// Only the first 128 options can be tracked,
+    /// for later ones see [`ParseError::UntrackedKey`].
+    ///
+    /// ```
+    /// # use miniarg::ParseError;
+    /// let cmdline = "executable -file a -stdin";
+    /// let mut args = miniarg::parse(&cmdline, &["file", "stdin"])
+    ///     .conflicts(&[("file", "stdin")]);
+    /// assert_eq!(args.next(), Some(Ok((&"file", "a"))));
+    /// assert_eq!(args.next(), Some(Ok((&"stdin", ""))));
+    /// assert_eq!(
+    ///     args.next(),
+    ///     Some(Err(ParseError::Conflict { key: "file", conflicts_with: "stdin" }))
+    /// );
+    /// assert_eq!(args.next(), None);
+    /// ```
+    ///
+    /// [`ParseError::Conflict`]: enum.ParseError.html#variant.Conflict
+    /// [`ParseError::UntrackedKey`]: enum.ParseError.html#variant.UntrackedKey
+    #[must_use]
+    pub fn conflicts(mut self, pairs: &'b [(&'a str, &'a str)]) -> Self {
+        for (key, other) in pairs {
+            self.track(key);
+            self.track(other);
+        }
+        self.conflicts = pairs;
+        self
+    }
+
+    /// Declare keys that can only be used together with another key.
+    ///
+    /// Each pair `(key, dependency)` means that `key` requires `dependency`.
+    /// After all arguments have been parsed, the iterator yields a
+    /// [`ParseError::MissingDependency`] for each pair where `key` occurred,
+    /// but `dependency` didn't.
+    ///
+    /// Only the first 128 options can be tracked,
+    /// for later ones see [`ParseError::UntrackedKey`].
+    ///
+    /// ```
+    /// # use miniarg::ParseError;
+    /// let cmdline = "executable -key a";
+    /// let mut args = miniarg::parse(&cmdline, &["key", "cert"])
+    ///     .requires(&[("key", "cert")]);
+    /// assert_eq!(args.next(), Some(Ok((&"key", "a"))));
+    /// assert_eq!(
+    ///     args.next(),
+    ///     Some(Err(ParseError::MissingDependency { key: "key", requires: "cert" }))
+    /// );
+    /// assert_eq!(args.next(), None);
+    /// ```
+    ///
+    /// [`ParseError::MissingDependency`]: enum.ParseError.html#variant.MissingDependency
+    /// [`ParseError::UntrackedKey`]: enum.ParseError.html#variant.UntrackedKey
+    #[must_use]
+    pub fn requires(mut self, pairs: &'b [(&'a str, &'a str)]) -> Self {
+        for (key, dependency) in pairs {
+            self.track(key);
+            self.track(dependency);
+        }
+        self.requires = pairs;
+        self
+    }
+
+    /// Declare default values for keys.
+    ///
+    /// After all arguments have been parsed, the iterator yields each key
+    /// that didn't occur together with its default value.
+    /// These come before any errors of the other checks,
+    /// which count the keys as present.
+    ///
+    /// Only the first 128 options can be tracked,
+    /// for later ones see [`ParseError::UntrackedKey`].
+    ///
+    /// ```
+    /// let cmdline = "executable -host example.com";
+    /// let mut args = miniarg::parse(&cmdline, &["host", "port"])
+    ///     .defaults(&[("port", "8080")]);
+    /// assert_eq!(args.next(), Some(Ok((&"host", "example.com"))));
+    /// assert_eq!(args.next(), Some(Ok((&"port", "8080"))));
+    /// assert_eq!(args.next(), None);
+    /// ```
+    ///
+    /// [`ParseError::UntrackedKey`]: enum.ParseError.html#variant.UntrackedKey
+    #[must_use]
+    pub fn defaults(mut self, defaults: &'b [(&'a str, &'a str)]) -> Self {
+        defaults.iter().for_each(|(key, _)| self.track(key));
+        self.defaults = defaults;
+        self
+    }
+
+    /// Declare environment variables as fallbacks for keys.
+    ///
+    /// Each pair `(key, variable)` means that the value of `key` is taken
+    /// from the environment variable `variable` if the key didn't occur.
+    /// After all arguments have been parsed, the iterator yields these keys
+    /// together with the values of the variables.
+    /// The cmdline takes precedence over the environment,
+    /// which takes precedence over [`defaults`].
+    /// Keys taken from the environment count as present for the other checks,
+    /// like [`required`].
+    ///
+    /// Only the first 128 options can be tracked,
+    /// for later ones see [`ParseError::UntrackedKey`].
+    ///
+    /// The variables are looked up in the [`Environment`] set by [`env_source`].
+    /// With `std`, this is [`ProcessEnv`] by default;
+    /// without `std`, no variables are looked up unless a source is set.
+    ///
+    /// ```
+    /// let env = |name: &str| (name == "MYAPP_PORT").then_some("80");
+    /// let mut args = miniarg::parse("executable", &["port"])
+    ///     .env(&[("port", "MYAPP_PORT")])
+    ///     .env_source(&env);
+    /// assert_eq!(args.next(), Some(Ok((&"port", "80"))));
+    /// assert_eq!(args.next(), None);
+    /// ```
+    ///
+    /// [`defaults`]: #method.defaults
+    /// [`required`]: #method.required
+    /// [`env_source`]: #method.env_source
+    /// [`Environment`]: trait.Environment.html
+    /// [`ProcessEnv`]: struct.ProcessEnv.html
+    /// [`ParseError::UntrackedKey`]: enum.ParseError.html#variant.UntrackedKey
+    #[must_use]
+    pub fn env(mut self, vars: &'b [(&'a str, &'a str)]) -> Self {
+        vars.iter().for_each(|(key, _)| self.track(key));
+        self.env = vars;
+        self
+    }
+
+    /// Set where the environment variables declared with [`env`] are looked up.
+    ///
+    /// [`env`]: #method.env
+    #[must_use]
+    pub fn env_source(mut self, source: &'b dyn Environment<'a>) -> Self {
+        self.env_source = Some(source);
+        self
+    }
+
+    /// Declare keys that are flags.
+    ///
+    /// Flags don't take a value: `-key` yields the key with the value `"true"`.
+    /// They can also be negated: `-no-key` yields the key with the value `"false"`,
+    /// so that later arguments can turn off earlier ones.
+    /// (If there is a key called `no-key`, that one is used instead.)
+    ///
+    /// ```
+    /// let cmdline = "executable -color -no-color -output file";
+    /// let mut args = miniarg::parse(&cmdline, &["color", "output"]).flags(&["color"]);
+    /// assert_eq!(args.next(), Some(Ok((&"color", "true"))));
+    /// assert_eq!(args.next(), Some(Ok((&"color", "false"))));
+    /// assert_eq!(args.next(), Some(Ok((&"output", "file"))));
+    /// assert_eq!(args.next(), None);
+    /// ```
+    #[must_use]
+    pub fn flags(mut self, keys: &'b [&'a str]) -> Self {
+        self.flags = keys;
+        self
+    }
+
+    /// Declare how many values keys take.
+    ///
+    /// By default, each key takes exactly one value.
+    /// Keys that take multiple values are yielded once for each of them.
+    /// If there are not enough values, the iterator yields a [`ParseError::MissingValue`].
+    ///
+    /// ```
+    /// # use miniarg::{Arity, ParseError};
+    /// let cmdline = "executable -range 0x1000 0x2000 -define A B C -range 0x3000";
+    /// let mut args = miniarg::parse(&cmdline, &["range", "define"])
+    ///     .arity(&[("range", Arity::Exactly(2)), ("define", Arity::Greedy)]);
+    /// assert_eq!(args.next(), Some(Ok((&"range", "0x1000"))));
+    /// assert_eq!(args.next(), Some(Ok((&"range", "0x2000"))));
+    /// assert_eq!(args.next(), Some(Ok((&"define", "A"))));
+    /// assert_eq!(args.next(), Some(Ok((&"define", "B"))));
+    /// assert_eq!(args.next(), Some(Ok((&"define", "C"))));
+    /// assert_eq!(args.next(), Some(Ok((&"range", "0x3000"))));
+    /// assert_eq!(
+    ///     args.next(),
+    ///     Some(Err(ParseError::MissingValue { key: "range", expected: 2, got: 1 }))
+    /// );
+    /// assert_eq!(args.next(), None);
+    /// ```
+    ///
+    /// The values of all occurrences are yielded the same way,
+    /// use [`grouped`] to tell them apart.
+    ///
+    /// [`ParseError::MissingValue`]: enum.ParseError.html#variant.MissingValue
+    /// [`grouped`]: #method.grouped
+    #[must_use]
+    pub fn arity(mut self, arities: &'b [(&'a str, Arity)]) -> Self {
+        self.arities = arities;
+        self
+    }
+
+    /// Yield the index of the key occurrence with each value.
+    ///
+    /// The keys are numbered in the order they occur, starting at 0,
+    /// so all values with the same index belong to the same occurrence.
+    /// Keys taken from the environment or the defaults come last.
+    ///
+    /// ```
+    /// # use miniarg::Arity;
+    /// let cmdline = "executable -define A B -define C";
+    /// let mut args = miniarg::parse(&cmdline, &["define"])
+    ///     .arity(&[("define", Arity::Greedy)])
+    ///     .grouped();
+    /// assert_eq!(args.next(), Some(Ok((&"define", 0, "A"))));
+    /// assert_eq!(args.next(), Some(Ok((&"define", 0, "B"))));
+    /// assert_eq!(args.next(), Some(Ok((&"define", 1, "C"))));
+    /// assert_eq!(args.next(), None);
+    /// ```
+    #[must_use]
+    pub fn grouped(self) -> Grouped<'a, 'b, T, S> {
+        Grouped::new(self)
+    }
+
+    /// Report a key without a value as an error.
+    ///
+    /// By default, a key that takes one value may be given without it
+    /// (this can be useful for `-help`), at the end or followed by another key,
+    /// and is then yielded with an empty value, just like `-key ""`.
+    /// With this, it results in a [`ParseError::MissingValue`] instead.
+    /// (Use [`flags`] for keys that don't take a value.)
+    ///
+    /// ```
+    /// # use miniarg::ParseError;
+    /// let mut args = miniarg::parse("executable -key", &["key"]).strict_values();
+    /// assert_eq!(
+    ///     args.next(),
+    ///     Some(Err(ParseError::MissingValue { key: "key", expected: 1, got: 0 }))
+    /// );
+    /// assert_eq!(args.next(), None);
+    /// ```
+    ///
+    /// [`ParseError::MissingValue`]: enum.ParseError.html#variant.MissingValue
+    /// [`flags`]: #method.flags
+    #[must_use]
+    pub fn strict_values(mut self) -> Self {
+        self.strict_values = true;
+        self
+    }
+
+    /// Get how many values the key `name` takes.
+    fn arity_of(&self, name: &str) -> Arity {
+        self.arities
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|&(_, arity)| arity)
+            .unwrap_or_default()
+    }
+
+    /// Get the next argument, or `None` if there are none left.
+    fn next_arg(&mut self) -> Option<ArgResult<'a, S::Item>> {
+        if let Some(arg) = self.pending.take() {
+            return Some(arg);
+        }
+        match self.finished {
+            None => self.args.next().map(Argument::into_argument),
+            Some(_) => None,
+        }
+    }
+
+    /// Handle the end of the arguments.
+    fn end(&mut self) -> Option<<Self as Iterator>::Item> {
+        let Expect::Values {
+            option,
+            key,
+            arity,
+            got,
+        } = core::mem::replace(&mut self.expect, Expect::Key)
+        else {
+            return self.finish();
+        };
+        self.finished.get_or_insert(0);
+        self.missing_values(option, key, arity, got)
+            .or_else(|| self.finish())
+    }
+
+    /// Handle the values of a key ending after `got` of them.
+    ///
+    /// Returns `None` if that's fine.
+    fn missing_values(
+        &self,
+        option: &'b T,
+        key: <S::Item as Argument<'a>>::Str,
+        arity: Arity,
+        got: usize,
+    ) -> Option<<Self as Iterator>::Item> {
+        match arity {
+            // just a key, this is used for `-help`
+            Arity::Exactly(1) if got == 0 && !self.strict_values => Some(Ok((option, "".into()))),
+            Arity::Exactly(expected) if got < expected => {
+                Some(Err(ParseError::MissingValue { key, expected, got }))
+            }
+            Arity::Greedy if got == 0 => Some(Err(ParseError::MissingValue {
+                key,
+                expected: 1,
+                got,
+            })),
+            _ => None,
+        }
+    }
+
+    /// Declare keys whose values may look like keys.
+    ///
+    /// Values that start with a dash are ambiguous, so these rules apply:
+    ///
+    /// * `-` on its own is always a value (it often means stdin)
+    /// * negative numbers (like `-16` or `-.5`) are always values
+    /// * anything else starting with a dash is a key, so it ends the values of the last key,
+    ///   unless that key is declared here (then all of its values are taken as they are)
+    ///
+    /// ```
+    /// let cmdline = "executable -offset -16 -input - -args -v -q";
+    /// let mut args = miniarg::parse(&cmdline, &["offset", "input", "args"])
+    ///     .allow_hyphen_values(&["args"])
+    ///     .arity(&[("args", miniarg::Arity::Exactly(2))]);
+    /// assert_eq!(args.next(), Some(Ok((&"offset", "-16"))));
+    /// assert_eq!(args.next(), Some(Ok((&"input", "-"))));
+    /// assert_eq!(args.next(), Some(Ok((&"args", "-v"))));
+    /// assert_eq!(args.next(), Some(Ok((&"args", "-q"))));
+    /// assert_eq!(args.next(), None);
+    /// ```
+    #[must_use]
+    pub fn allow_hyphen_values(mut self, keys: &'b [&'a str]) -> Self {
+        self.allow_hyphen_values = keys;
+        self
+    }
+
+    /// Set the prefixes that keys start with.
+    ///
+    /// By default, this is just `-`.
+    /// If multiple prefixes match, the longest one is used.
+    /// With an empty prefix, every argument in the place of a key is a key,
+    /// so this parses `key value key value` streams.
+    ///
+    /// ```
+    /// let cmdline = "executable /key value +other value";
+    /// let mut args = miniarg::parse(&cmdline, &["key", "other"]).prefixes(&["/", "+"]);
+    /// assert_eq!(args.next(), Some(Ok((&"key", "value"))));
+    /// assert_eq!(args.next(), Some(Ok((&"other", "value"))));
+    /// assert_eq!(args.next(), None);
+    ///
+    /// let cmdline = "executable key value";
+    /// let mut args = miniarg::parse(&cmdline, &["key"]).prefixes(&[""]);
+    /// assert_eq!(args.next(), Some(Ok((&"key", "value"))));
+    /// assert_eq!(args.next(), None);
+    /// ```
+    #[must_use]
+    pub fn prefixes(mut self, prefixes: &'b [&'a str]) -> Self {
+        self.prefixes = prefixes;
+        self
+    }
+
+    /// Declare keys that still work, but shouldn't be used anymore.
+    ///
+    /// Each pair `(key, note)` contains a note for the user (like `use -foo`).
+    /// The keys are parsed as usual, [`warnings`] reports the ones that occurred.
+    ///
+    /// Only the first 128 options can be tracked,
+    /// for later ones see [`ParseError::UntrackedKey`].
+    ///
+    /// [`warnings`]: #method.warnings
+    /// [`ParseError::UntrackedKey`]: enum.ParseError.html#variant.UntrackedKey
+    #[must_use]
+    pub fn deprecated(mut self, notes: &'b [(&'a str, &'a str)]) -> Self {
+        notes.iter().for_each(|(key, _)| self.track(key));
+        self.deprecated = notes;
+        self
+    }
+
+    /// Get the warnings about the arguments parsed so far.
+    ///
+    /// They don't interrupt parsing, so call this after iterating
+    /// (for example with [`by_ref`]).
+    ///
+    /// ```
+    /// # use miniarg::ParseWarning;
+    /// let cmdline = "executable -old value";
+    /// let mut args = miniarg::parse(&cmdline, &["new", "old"])
+    ///     .deprecated(&[("old", "use -new")]);
+    /// assert_eq!(args.by_ref().collect::<Vec<_>>(), vec![Ok((&"old", "value"))]);
+    /// assert_eq!(
+    ///     args.warnings().collect::<Vec<_>>(),
+    ///     vec![ParseWarning::Deprecated { key: "old", note: "use -new" }]
+    /// );
+    /// ```
+    ///
+    /// [`by_ref`]: https://doc.rust-lang.org/core/iter/trait.Iterator.html#method.by_ref
+    pub fn warnings(&self) -> impl Iterator<Item = ParseWarning<'a>> {
+        self.deprecated
+            .iter()
+            .filter(|(key, _)| self.occurred(key))
+            .map(|&(key, note)| ParseWarning::Deprecated { key, note })
+    }
+
+    /// Get the (longest) prefix `arg` starts with.
+    fn key_prefix(&self, arg: &str) -> Option<&'a str> {
+        self.prefixes
+            .iter()
+            .copied()
+            .filter(|prefix| arg.starts_with(prefix))
+            .max_by_key(|prefix| prefix.len())
+    }
+
+    /// Check whether `arg` looks like a key (and not like a value).
+    ///
+    /// Arguments that start with an empty prefix don't.
+    fn looks_like_key(&self, arg: &str) -> bool {
+        self.key_prefix(arg)
+            .is_some_and(|prefix| !prefix.is_empty() && is_key_name(&arg[prefix.len()..]))
+    }
+
+    /// Check whether `arg` can be a value of the key `name`.
+    fn is_value(&self, arg: &str, name: &str) -> bool {
+        !self.looks_like_key(arg) || self.allow_hyphen_values.contains(&name)
+    }
+
+    /// Check whether the key `name` is a flag.
+    fn is_flag(&self, name: &str) -> bool {
+        self.flags.contains(&name)
+    }
+
+    /// Look up the environment variable for the key `name`, if it has one.
+    fn env_value(&self, name: &str) -> Option<&'a str> {
+        let source = self.env_source?;
+        self.env
+            .iter()
+            .filter(|(key, _)| *key == name)
+            .find_map(|(_, var)| source.var(var))
+    }
+
+    /// Count an occurrence of the key `name`.
+    ///
+    /// Returns `false` if it occurs more often than allowed.
+    fn count(&mut self, name: &str) -> bool {
+        let mut allowed = true;
+        for ((key, limit), count) in self.occurrences.iter().zip(self.counts.iter_mut()) {
+            if *key == name {
+                *count = count.saturating_add(1);
+                if limit.max().is_some_and(|max| *count > max) {
+                    allowed = false;
+                }
+            }
+        }
+        allowed
+    }
+
+    /// Mark the option at `index` called `name` as present and count it.
+    ///
+    /// Returns `false` if it occurs more often than allowed.
+    fn mark(&mut self, index: usize, name: &str) -> bool {
+        if index < MAX_TRACKED {
+            self.seen |= 1 << index;
+        }
+        let allowed = self.count(name);
+        // rejected occurrences don't yield values, so they don't get an index
+        if allowed {
+            self.keys_seen += 1;
+        }
+        allowed
+    }
+
+    /// Find the index of the option called `name`.
+    fn find_option(&self, name: &str) -> Option<usize> {
+        find_option(self.options, name)
+    }
+
+    /// Check whether the option at `index` occurred.
+    ///
+    /// Options that can't be tracked never count as seen.
+    fn is_seen(&self, index: usize) -> bool {
+        index < MAX_TRACKED && self.seen & (1 << index) != 0
+    }
+
+    /// Remember the key `name` if it's an option that can't be tracked.
+    fn track(&mut self, name: &'a str) {
+        if self
+            .find_option(name)
+            .is_some_and(|index| index >= MAX_TRACKED)
+        {
+            self.untracked.get_or_insert(name);
         }
     }
+
+    /// Check whether the key `name` occurred.
+    fn occurred(&self, name: &str) -> bool {
+        self.find_option(name)
+            .is_some_and(|index| self.is_seen(index))
+    }
+
+    /// Get the defaults and errors that are only known after all arguments have been parsed.
+    fn finish(&mut self) -> Option<<Self as Iterator>::Item> {
+        let mut pos = self.finished.unwrap_or(0);
+        let item = loop {
+            let Some(result) = self.check(pos) else {
+                break None;
+            };
+            pos += 1;
+            if let Some(item) = result {
+                break Some(item);
+            }
+        };
+        self.finished = Some(pos);
+        item
+    }
+
+    /// Run the check number `pos` of the ones after the last argument.
+    ///
+    /// Returns `None` if there are no more checks
+    /// and `Some(None)` if this one has nothing to report.
+    fn check(&mut self, pos: usize) -> Option<Option<<Self as Iterator>::Item>> {
+        if let Some(key) = self.untracked {
+            // the other checks would be wrong
+            return (pos == 0).then(|| Some(Err(ParseError::UntrackedKey(key.into()))));
+        }
+        if let Some(&(key, _)) = self.env.get(pos) {
+            let Some((index, value)) = self
+                .find_option(key)
+                .filter(|&index| !self.is_seen(index))
+                .zip(self.env_value(key))
+            else {
+                return Some(None);
+            };
+            // a value from the environment counts as present for the following checks
+            self.mark(index, key);
+            let options = self.options;
+            return Some(Some(Ok((&options[index], value.into()))));
+        }
+        let pos = pos - self.env.len();
+        if let Some(&(key, default)) = self.defaults.get(pos) {
+            let Some(index) = self.find_option(key).filter(|&index| !self.is_seen(index)) else {
+                return Some(None);
+            };
+            // a default counts as present for the following checks
+            self.mark(index, key);
+            let options = self.options;
+            return Some(Some(Ok((&options[index], default.into()))));
+        }
+        let pos = pos - self.defaults.len();
+        if let Some(&key) = self.required.get(pos) {
+            if !self.occurred(key) {
+                return Some(Some(Err(ParseError::MissingKey(key.into()))));
+            }
+            return Some(None);
+        }
+        let pos = pos - self.required.len();
+        if let Some(&(key, limit)) = self.occurrences.get(pos) {
+            let count = self.counts[pos];
+            if count == 0 && limit.min() > 0 {
+                return Some(Some(Err(ParseError::MissingKey(key.into()))));
+            } else if count < limit.min() {
+                return Some(Some(Err(ParseError::TooFewOccurrences {
+                    key: key.into(),
+                    min: limit.min(),
+                    got: count,
+                })));
+            }
+            return Some(None);
+        }
+        let pos = pos - self.occurrences.len();
+        if let Some(&(key, other)) = self.conflicts.get(pos) {
+            if self.occurred(key) && self.occurred(other) {
+                return Some(Some(Err(ParseError::Conflict {
+                    key: key.into(),
+                    conflicts_with: other.into(),
+                })));
+            }
+            return Some(None);
+        }
+        let pos = pos - self.conflicts.len();
+        if let Some(&(key, dependency)) = self.requires.get(pos) {
+            if self.occurred(key) && !self.occurred(dependency) {
+                return Some(Some(Err(ParseError::MissingDependency {
+                    key: key.into(),
+                    requires: dependency.into(),
+                })));
+            }
+            return Some(None);
+        }
+        None
+    }
+}
+
+#[cfg(feature = "derive")]
+impl<'a, 'b, T, S> ArgumentIterator<'a, 'b, T, S>
+where
+    T: Key + ToString,
+    S: Iterator,
+    S::Item: Argument<'a>,
+{
+    /// Handle `-help` and `-version` without declaring them.
+    ///
+    /// The help text is [`Key::help`] and the version is [`Key::version`],
+    /// see [`Builtins`] for details.
+    ///
+    /// [`Key::help`]: trait.Key.html#method.help
+    /// [`Key::version`]: trait.Key.html#tymethod.version
+    /// [`Builtins`]: struct.Builtins.html
+    #[must_use]
+    pub fn builtins(self) -> Builtins<'static, Self> {
+        Builtins::new(self, T::help(), T::version())
+    }
 }
 
 impl<'a, 'b, T, S> Iterator for ArgumentIterator<'a, 'b, T, S>
 where
     T: ToString,
-    S: Iterator<Item = &'a str>,
+    S: Iterator,
+    S::Item: Argument<'a>,
 {
-    type Item = Result<(&'b T, &'a str), ParseError<'a>>;
+    type Item = Result<
+        (&'b T, <S::Item as Argument<'a>>::Str),
+        ParseError<'a, <S::Item as Argument<'a>>::Str>,
+    >;
 
     /// Get the next key pair or an error.
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            let Some(arg) = self.args.next() else {
-                return match self.last {
-                    Some(l) => {
-                        self.last = None;
-                        Some(Ok((l, "")))
-                    }
-                    None => None,
-                };
+            let Some(arg) = self.next_arg() else {
+                return self.end();
             };
-            if let Some(l) = self.last {
-                // the last element was a key
-                self.last = None;
-                return Some(Ok((l, arg)));
+            let arg = match arg {
+                Ok(arg) => arg,
+                Err(e) => {
+                    // the error takes the place of the value
+                    self.expect = Expect::Key;
+                    return Some(Err(e));
+                }
+            };
+            match core::mem::replace(&mut self.expect, Expect::Key) {
+                Expect::Key => (),
+                Expect::Values {
+                    option,
+                    key,
+                    arity,
+                    got,
+                } => {
+                    if !self.is_value(arg.as_ref(), key.as_ref()) {
+                        // this is the next key
+                        self.pending = Some(Ok(arg));
+                        match self.missing_values(option, key, arity, got) {
+                            Some(item) => return Some(item),
+                            None => continue,
+                        }
+                    }
+                    let got = got + 1;
+                    if arity.takes_more(got) {
+                        self.expect = Expect::Values {
+                            option,
+                            key,
+                            arity,
+                            got,
+                        };
+                    }
+                    return Some(Ok((option, arg)));
+                }
+                Expect::Skip {
+                    arity,
+                    got,
+                    allow_hyphen_values,
+                } => {
+                    // the values of a rejected key
+                    if self.looks_like_key(arg.as_ref()) && !allow_hyphen_values {
+                        self.pending = Some(Ok(arg));
+                    } else if arity.takes_more(got + 1) {
+                        self.expect = Expect::Skip {
+                            arity,
+                            got: got + 1,
+                            allow_hyphen_values,
+                        };
+                    }
+                    continue;
+                }
             }
             // the next element has to be a key
-            if let Some(a) = arg.strip_prefix("-") {
-                self.last = self.options.iter().find(|o| {
-                    cfg_if! {
-                        if #[cfg(any(feature = "alloc", feature = "std"))] {
-                            first_lower(&o.to_string())
-                        } else {
-                            o.to_string()
-                        }
+            if let Some(prefix) = self.key_prefix(arg.as_ref()) {
+                let a = &arg.as_ref()[prefix.len()..];
+                // negated flags are only considered if there is no such key
+                let (name, flag) = match a.strip_prefix("no-") {
+                    Some(flag) if self.find_option(a).is_none() && self.is_flag(flag) => {
+                        (flag, Some("false"))
+                    }
+                    _ => (a, self.is_flag(a).then_some("true")),
+                };
+                let Some(index) = self.find_option(name) else {
+                    if !prefix.is_empty() && !is_key_name(a) {
+                        // this is `-` or a negative number
+                        return Some(Err(ParseError::NotAKey(arg)));
                     }
-                } == a);
-                if self.last.is_none() {
-                    return Some(Err(ParseError::UnknownKey(a)));
+                    return Some(Err(ParseError::UnknownKey(arg.without_prefix(prefix))));
+                };
+                let arity = self.arity_of(name);
+                let takes_values = flag.is_none() && arity.takes_more(0);
+                if !self.mark(index, name) {
+                    if takes_values {
+                        self.expect = Expect::Skip {
+                            arity,
+                            got: 0,
+                            allow_hyphen_values: self.allow_hyphen_values.contains(&name),
+                        };
+                    }
+                    return Some(Err(ParseError::DuplicateKey(arg.without_prefix(prefix))));
+                }
+                let option = self.options.get(index)?;
+                if !takes_values {
+                    // flags (and keys without values) don't have a value
+                    return Some(Ok((option, flag.unwrap_or_default().into())));
                 }
+                self.expect = Expect::Values {
+                    option,
+                    key: arg.without_prefix(prefix),
+                    arity,
+                    got: 0,
+                };
             } else {
                 return Some(Err(ParseError::NotAKey(arg)));
             }
@@ -231,25 +1373,239 @@ where
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 #[non_exhaustive]
 /// Errors occurred during parsing the command line.
-pub enum ParseError<'a> {
+///
+/// The strings contained in the errors are usually borrowed from the cmdline,
+/// but they can also be owned, see [`Argument`] for details.
+///
+/// [`Argument`]: trait.Argument.html
+pub enum ParseError<'a, S = &'a str> {
     /// expected a key, but argument didn't start with a dash
-    NotAKey(&'a str),
+    NotAKey(S),
     /// key is not accepted
-    UnknownKey(&'a str),
+    UnknownKey(S),
+    /// a quote was opened at byte offset `start`, but never closed
+    UnterminatedQuote {
+        start: usize,
+    },
+    /// argument is not valid unicode (this contains a lossy conversion)
+    NotUnicode(S),
+    /// a required key didn't occur
+    MissingKey(S),
+    /// key occurred more often than allowed
+    DuplicateKey(S),
+    /// key occurred less often than required
+    TooFewOccurrences {
+        key: S,
+        min: usize,
+        got: usize,
+    },
+    /// two keys that can't be used together both occurred
+    Conflict {
+        key: S,
+        conflicts_with: S,
+    },
+    /// key occurred, but another key it requires didn't
+    MissingDependency {
+        key: S,
+        requires: S,
+    },
+    /// key has constraints that can't be checked (it isn't among the first 128 options
+    /// or the first 32 occurrence limits), this replaces the other checks
+    UntrackedKey(S),
+    /// key takes `expected` values, but only `got` followed
+    MissingValue {
+        key: S,
+        expected: usize,
+        got: usize,
+    },
+    /// a response file couldn't be loaded
+    UnreadableResponseFile(S),
+    /// a response file (indirectly) contains itself
+    ResponseFileCycle(S),
+    /// response files are nested too deeply
+    ResponseFilesTooDeep(S),
+    /// expected a subcommand, but there were no arguments
+    MissingSubcommand,
+    /// subcommand is not accepted
+    UnknownSubcommand(S),
     // the default error
     _Unknown,
+    // this is never constructed, it just holds the lifetime
+    #[doc(hidden)]
+    _Lifetime(PhantomData<&'a ()>, Infallible),
 }
 
-impl fmt::Display for ParseError<'_> {
+impl<S> fmt::Display for ParseError<'_, S>
+where
+    S: fmt::Display,
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         match self {
             Self::NotAKey(s) => write!(f, "expected '{s}' to start with a dash"),
             Self::UnknownKey(s) => write!(f, "'{s}' is not a known key"),
+            Self::UnterminatedQuote { start } => {
+                write!(f, "the quote at offset {start} is never closed")
+            }
+            Self::NotUnicode(s) => write!(f, "'{s}' is not valid unicode"),
+            Self::MissingKey(s) => write!(f, "the key '{s}' is required"),
+            Self::DuplicateKey(s) => write!(f, "the key '{s}' occurs too often"),
+            Self::TooFewOccurrences { key, min, got } => {
+                write!(
+                    f,
+                    "the key '{key}' has to occur at least {min} times, but occurs {got} times"
+                )
+            }
+            Self::Conflict {
+                key,
+                conflicts_with,
+            } => write!(
+                f,
+                "the keys '{key}' and '{conflicts_with}' can't be used together"
+            ),
+            Self::MissingDependency { key, requires } => {
+                write!(f, "the key '{key}' requires the key '{requires}'")
+            }
+            Self::UntrackedKey(s) => write!(f, "the constraints of the key '{s}' can't be checked"),
+            Self::MissingValue { key, expected, got } => {
+                write!(f, "the key '{key}' takes {expected} values, but got {got}")
+            }
+            Self::UnreadableResponseFile(s) => write!(f, "failed to load the response file '{s}'"),
+            Self::ResponseFileCycle(s) => write!(f, "the response file '{s}' contains itself"),
+            Self::ResponseFilesTooDeep(s) => {
+                write!(f, "the response file '{s}' is nested too deeply")
+            }
+            Self::MissingSubcommand => write!(f, "a subcommand is required"),
+            Self::UnknownSubcommand(s) => write!(f, "'{s}' is not a known subcommand"),
             _ => write!(f, "unknown parse error"),
         }
     }
 }
-impl Error for ParseError<'_> {}
+impl<S> Error for ParseError<'_, S> where S: fmt::Debug + fmt::Display {}
+
+impl<'a, S> ParseError<'a, S>
+where
+    S: AsRef<str>,
+{
+    /// Copy the contained strings, so that the error doesn't borrow the cmdline anymore.
+    ///
+    /// This requires the `alloc` feature, see [`into_inline`] for an alternative without it.
+    ///
+    /// [`into_inline`]: #method.into_inline
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn into_owned(self) -> OwnedParseError {
+        self.map(|s| s.as_ref().into())
+    }
+
+    /// Copy the contained strings into a fixed-size buffer of `N` bytes.
+    ///
+    /// Longer strings are truncated, see [`InlineStr`] for details.
+    ///
+    /// [`InlineStr`]: struct.InlineStr.html
+    #[must_use]
+    pub fn into_inline<const N: usize>(self) -> InlineParseError<N> {
+        self.map(|s| InlineStr::new(s.as_ref()))
+    }
+
+    /// Convert the contained strings.
+    fn map<R>(self, f: impl Fn(S) -> R) -> ParseError<'static, R> {
+        match self {
+            Self::NotAKey(s) => ParseError::NotAKey(f(s)),
+            Self::UnknownKey(s) => ParseError::UnknownKey(f(s)),
+            Self::UnterminatedQuote { start } => ParseError::UnterminatedQuote { start },
+            Self::NotUnicode(s) => ParseError::NotUnicode(f(s)),
+            Self::MissingKey(s) => ParseError::MissingKey(f(s)),
+            Self::DuplicateKey(s) => ParseError::DuplicateKey(f(s)),
+            Self::TooFewOccurrences { key, min, got } => ParseError::TooFewOccurrences {
+                key: f(key),
+                min,
+                got,
+            },
+            Self::Conflict {
+                key,
+                conflicts_with,
+            } => ParseError::Conflict {
+                key: f(key),
+                conflicts_with: f(conflicts_with),
+            },
+            Self::MissingDependency { key, requires } => ParseError::MissingDependency {
+                key: f(key),
+                requires: f(requires),
+            },
+            Self::UntrackedKey(s) => ParseError::UntrackedKey(f(s)),
+            Self::MissingValue { key, expected, got } => ParseError::MissingValue {
+                key: f(key),
+                expected,
+                got,
+            },
+            Self::UnreadableResponseFile(s) => ParseError::UnreadableResponseFile(f(s)),
+            Self::ResponseFileCycle(s) => ParseError::ResponseFileCycle(f(s)),
+            Self::ResponseFilesTooDeep(s) => ParseError::ResponseFilesTooDeep(f(s)),
+            Self::MissingSubcommand => ParseError::MissingSubcommand,
+            Self::UnknownSubcommand(s) => ParseError::UnknownSubcommand(f(s)),
+            Self::_Unknown => ParseError::_Unknown,
+            Self::_Lifetime(_, never) => match never {},
+        }
+    }
+}
+
+/// A [`ParseError`] that owns its strings.
+///
+/// This doesn't borrow from the cmdline, so it can be returned from `main`
+/// or stored in other errors. See [`ParseError::into_owned`].
+///
+/// [`ParseError`]: enum.ParseError.html
+/// [`ParseError::into_owned`]: enum.ParseError.html#method.into_owned
+#[cfg(feature = "alloc")]
+pub type OwnedParseError = ParseError<'static, String>;
+
+/// A [`ParseError`] that stores its strings inline.
+///
+/// This is like [`OwnedParseError`], but works without `alloc`.
+/// See [`ParseError::into_inline`].
+///
+/// [`ParseError`]: enum.ParseError.html
+/// [`OwnedParseError`]: type.OwnedParseError.html
+/// [`ParseError::into_inline`]: enum.ParseError.html#method.into_inline
+pub type InlineParseError<const N: usize> = ParseError<'static, InlineStr<N>>;
+
+#[cfg(feature = "alloc")]
+impl<'a> From<ParseError<'a>> for OwnedParseError {
+    fn from(e: ParseError<'a>) -> Self {
+        e.into_owned()
+    }
+}
+
+impl<S> From<SplitError> for ParseError<'_, S> {
+    fn from(e: SplitError) -> Self {
+        match e {
+            SplitError::UnterminatedQuote { start } => Self::UnterminatedQuote { start },
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[non_exhaustive]
+/// Problems with the command line that don't prevent parsing it.
+///
+/// See [`ArgumentIterator::warnings`].
+///
+/// [`ArgumentIterator::warnings`]: struct.ArgumentIterator.html#method.warnings
+pub enum ParseWarning<'a> {
+    /// a deprecated key occurred (`note` tells what to use instead)
+    Deprecated { key: &'a str, note: &'a str },
+}
+
+impl fmt::Display for ParseWarning<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Self::Deprecated { key, note: "" } => {
+                write!(f, "the key '{key}' is deprecated")
+            }
+            Self::Deprecated { key, note } => write!(f, "the key '{key}' is deprecated: {note}"),
+        }
+    }
+}
 
 #[cfg(all(feature = "derive", not(feature = "alloc")))]
 compile_error!("at least the `alloc` feature is currently required to get the derive feature");
@@ -277,19 +1633,219 @@ compile_error!("at least the `alloc` feature is currently required to get the de
 /// assert_eq!(args, vec![(&MyKeys::Foo, "value"), (&MyKeys::Bar, "value")]);
 /// # Ok(())
 /// # }
+/// ```
+///
+/// # Attributes
+///
+/// The kinds can be configured with `#[miniarg(...)]` attributes:
+///
+/// * `required`: the key has to occur, see [`ArgumentIterator::required`]
+/// * `once`, `min = N`, `max = N`: how often the key may occur,
+///   see [`ArgumentIterator::occurrences`]
+/// * `conflicts_with = "key"`: the keys can't be used together,
+///   see [`ArgumentIterator::conflicts`]
+/// * `requires = "key"`: the key can only be used together with the other one,
+///   see [`ArgumentIterator::requires`]
+/// * `default = "value"`: the value if the key doesn't occur,
+///   see [`ArgumentIterator::defaults`] (this is also shown in the help text)
+/// * `env = "VAR"`: the environment variable to use if the key doesn't occur,
+///   see [`ArgumentIterator::env`] (this is also shown in the help text)
+/// * `flag`: the key doesn't take a value and can be negated with `-no-key`,
+///   see [`ArgumentIterator::flags`]
+/// * `arity = N`, `greedy`: how many values the key takes,
+///   see [`ArgumentIterator::arity`]
+/// * `allow_hyphen_values`: the values of the key may look like keys,
+///   see [`ArgumentIterator::allow_hyphen_values`]
+/// * `value_name = "NAME"`: the placeholder for the value in the usage line,
+///   see [`usage`](#tymethod.usage)
+/// * `hidden`: the key works, but isn't shown in the help text and the usage line
+/// * `deprecated = "note"`: the key works, but yields a warning,
+///   see [`ArgumentIterator::deprecated`]
+///
+/// ```
+/// # use miniarg::*;
+/// # use std::fmt;
+/// #[derive(Debug, Key, PartialEq, Eq, Hash)]
+/// enum MyKeys {
+///     #[miniarg(required)]
+///     Config,
+/// }
+/// let mut args = MyKeys::parse("executable");
+/// assert_eq!(args.next(), Some(Err(ParseError::MissingKey("config"))));
+/// ```
+///
+/// [`ArgumentIterator::required`]: struct.ArgumentIterator.html#method.required
+/// [`ArgumentIterator::occurrences`]: struct.ArgumentIterator.html#method.occurrences
+/// [`ArgumentIterator::conflicts`]: struct.ArgumentIterator.html#method.conflicts
+/// [`ArgumentIterator::requires`]: struct.ArgumentIterator.html#method.requires
+/// [`ArgumentIterator::defaults`]: struct.ArgumentIterator.html#method.defaults
+/// [`ArgumentIterator::env`]: struct.ArgumentIterator.html#method.env
+/// [`ArgumentIterator::flags`]: struct.ArgumentIterator.html#method.flags
+/// [`ArgumentIterator::arity`]: struct.ArgumentIterator.html#method.arity
+/// [`ArgumentIterator::allow_hyphen_values`]: struct.ArgumentIterator.html#method.allow_hyphen_values
+/// [`ArgumentIterator::deprecated`]: struct.ArgumentIterator.html#method.deprecated
 #[cfg(feature = "derive")]
 pub trait Key {
     /// Parse the cmdline.
     ///
     /// You'll get an iterator yielding key value pairs.
-    fn parse(cmdline: &str) -> ArgumentIterator<Self, SplitArgs>
+    fn parse(cmdline: &str) -> ArgumentIterator<'_, '_, Self, SplitArgs<'_>>
     where
-        Self: ToString + Sized;
+        Self: ToString + Sized + 'static,
+    {
+        Self::parse_from_iter(SplitArgs::new(cmdline))
+    }
+
+    /// Parse from a custom iterator.
+    ///
+    /// This is like [`parse_from_iter`], see there for details.
+    ///
+    /// [`parse_from_iter`]: fn.parse_from_iter.html
+    fn parse_from_iter<'a, S>(args: S) -> ArgumentIterator<'a, 'a, Self, S>
+    where
+        Self: ToString + Sized + 'static,
+        S: Iterator,
+        S::Item: Argument<'a>;
+
+    /// Parse the arguments of the current process.
+    ///
+    /// This is like [`parse_env`], see there for details.
+    ///
+    /// [`parse_env`]: fn.parse_env.html
+    #[cfg(feature = "std")]
+    fn parse_env() -> ArgumentIterator<'static, 'static, Self, EnvArgs>
+    where
+        Self: ToString + Sized + 'static,
+    {
+        Self::parse_from_iter(EnvArgs::new())
+    }
+
+    /// Apply the constraints declared with `#[miniarg(...)]` attributes.
+    ///
+    /// This is used by [`parse_from_iter`](#tymethod.parse_from_iter)
+    /// and for [`Subcommand`]s.
+    ///
+    /// [`Subcommand`]: trait.Subcommand.html
+    fn configure<'a, 'b, T, S>(
+        args: ArgumentIterator<'a, 'b, T, S>,
+    ) -> ArgumentIterator<'a, 'b, T, S>
+    where
+        T: ToString,
+        S: Iterator,
+        S::Item: Argument<'a>;
+
+    /// Get the names of the keys (without the leading dash).
+    fn keys() -> &'static [&'static str];
+
+    /// Get the kind for the key `name` (without the leading dash).
+    fn from_key(name: &str) -> Option<Self>
+    where
+        Self: Sized;
+
+    /// Parse a config file.
+    ///
+    /// This is like [`config::parse_config`] with the flags declared,
+    /// see there for details.
+    ///
+    /// [`config::parse_config`]: config/fn.parse_config.html
+    fn parse_config(config: &str) -> config::ConfigIterator<'_, 'static, Self>
+    where
+        Self: ToString + Sized + 'static;
 
     /// Get a help text.
     ///
-    /// This is being created from the enum kinds and their documentation comments.
+    /// This is being created from the enum kinds and their documentation comments,
+    /// one line per key (`-key\tdocumentation`).
+    /// The documentation comment of the enum itself is put in front of them.
     fn help_text() -> &'static str;
+
+    /// Get the documentation comment of the enum.
+    fn description() -> &'static str;
+
+    /// Get the names of the keys (without the leading dash) and their documentation.
+    fn help_entries() -> &'static [(&'static str, &'static str)];
+
+    /// Get a help text that is aligned into columns and wrapped.
+    ///
+    /// Unlike [`help_text`](#tymethod.help_text), this doesn't rely on tab stops.
+    /// See [`help::Help`] for details.
+    ///
+    /// [`help::Help`]: help/struct.Help.html
+    #[must_use]
+    fn help() -> help::Help<'static> {
+        help::Help::new(Self::help_entries()).description(Self::description())
+    }
+
+    /// Get a usage line, like `usage: tool [-verbose] -config <value> [-define <value>...]`.
+    ///
+    /// This is being created from the enum kinds and their attributes.
+    /// Optional keys are in brackets and keys that can occur multiple times
+    /// (or take multiple values) are followed by `...`.
+    /// The name of the tool is the name of the package.
+    /// To use the actual name of the program instead, see [`write_usage`](#method.write_usage).
+    fn usage() -> &'static str;
+
+    /// Get the usage line without `usage: ` and the name of the tool.
+    ///
+    /// This can be combined with [`ArgumentIterator::program_name`].
+    ///
+    /// [`ArgumentIterator::program_name`]: struct.ArgumentIterator.html#method.program_name
+    fn usage_args() -> &'static str;
+
+    /// Write a usage line with the name of the program to `f`.
+    ///
+    /// This is like [`usage`](#tymethod.usage), but doesn't need an allocation
+    /// to use a name that is only known at runtime (like [`ArgumentIterator::program_name`]).
+    ///
+    /// ```no_run
+    /// # use core::fmt;
+    /// # use miniarg::Key;
+    /// #[derive(Debug, Key)]
+    /// enum MyKeys {
+    ///     /// the input file
+    ///     #[miniarg(required)]
+    ///     Input,
+    /// }
+    ///
+    /// # #[cfg(feature = "std")] {
+    /// let args = MyKeys::parse_env();
+    /// let mut usage = String::new();
+    /// MyKeys::write_usage(args.program_name().unwrap_or("tool"), &mut usage).unwrap();
+    /// // usage: tool -input <value>
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This only fails if writing to `f` does.
+    ///
+    /// [`ArgumentIterator::program_name`]: struct.ArgumentIterator.html#method.program_name
+    fn write_usage<W>(program_name: &str, f: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        write!(f, "usage: {program_name}")?;
+        match Self::usage_args() {
+            "" => Ok(()),
+            args => write!(f, " {args}"),
+        }
+    }
+
+    /// Get the name and the version of the package, like `tool 1.0.0`.
+    ///
+    /// This is used for `-version`, see [`ArgumentIterator::builtins`].
+    ///
+    /// [`ArgumentIterator::builtins`]: struct.ArgumentIterator.html#method.builtins
+    fn version() -> &'static str;
+
+    /// Get a man page.
+    ///
+    /// This is being created from the enum kinds, their documentation comments
+    /// and their attributes, like the help text and the usage line.
+    /// See [`man::ManPage`] for details.
+    ///
+    /// [`man::ManPage`]: man/struct.ManPage.html
+    fn man_page() -> man::ManPage<'static>;
 }
 
 /// custom derive for the [`Key`] trait
@@ -298,6 +1854,45 @@ pub trait Key {
 #[cfg(feature = "derive")]
 pub use miniarg_derive::Key;
 
+// This is used by the generated code.
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::string::ToString;
+}
+
+/// custom derive for the [`Subcommand`] trait
+///
+/// [`Subcommand`]: trait.Subcommand.html
+#[cfg(feature = "derive")]
+pub use miniarg_derive::Subcommand;
+
+/// Check whether `rest` (after a non-empty prefix) can be the name of a key.
+///
+/// Nothing (like `-` on its own) and numbers (like in `-16`) can't.
+fn is_key_name(rest: &str) -> bool {
+    let rest = rest.strip_prefix('.').unwrap_or(rest);
+    !rest.is_empty() && !rest.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Find the index of the option called `name` in `options`.
+fn find_option<T: ToString>(options: &[T], name: &str) -> Option<usize> {
+    options.iter().position(|o| {
+        cfg_if! {
+            if #[cfg(any(feature = "alloc", feature = "std"))] {
+                first_lower(&o.to_string())
+            } else {
+                o.to_string()
+            }
+        }
+    } == name)
+}
+
+/// How many entries [`ArgumentIterator::occurrences`] can have.
+const MAX_OCCURRENCES: usize = 32;
+/// How many options [`ArgumentIterator`] can track (the bits of `seen`).
+const MAX_TRACKED: usize = 128;
+
 /// Turn the first character into lowercase.
 #[cfg(feature = "alloc")]
 fn first_lower(input: &str) -> String {
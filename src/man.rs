@@ -0,0 +1,284 @@
+//! Generates man pages.
+//!
+//! # Usage
+//!
+//! ```
+//! # use miniarg::man::{ManKey, ManPage};
+//! let keys = [
+//!     ManKey::new("port", "the serial port").value_name("PORT"),
+//!     ManKey::new("baud", "the baud rate").default("115200"),
+//!     ManKey::new("verbose", "more output").flag(),
+//! ];
+//! let page = ManPage::new("fwtool", &keys)
+//!     .description("Talks to a device.\nIt has to be connected via serial.")
+//!     .synopsis("-port <PORT> [-baud <value>] [-verbose]");
+//! assert_eq!(
+//!     page.to_string(),
+//!     r#".TH "FWTOOL" "1"
+//! .SH NAME
+//! fwtool \- Talks to a device.
+//! .SH SYNOPSIS
+//! .B fwtool
+//! \-port <PORT> [\-baud <value>] [\-verbose]
+//! .SH DESCRIPTION
+//! It has to be connected via serial.
+//! .SH OPTIONS
+//! .TP
+//! \fB\-port\fR \fIPORT\fR
+//! the serial port
+//! .TP
+//! \fB\-baud\fR \fIvalue\fR
+//! the baud rate
+//! .IP
+//! Default: 115200
+//! .TP
+//! \fB\-verbose\fR
+//! more output
+//! "#,
+//! );
+//! ```
+//!
+//! The first paragraph of the description is used as the short description
+//! (in the `NAME` section), the other ones are shown in the `DESCRIPTION` section.
+//! Newlines start a new paragraph.
+//!
+//! Keys don't have aliases in miniarg, so each key is listed under its name only.
+//!
+//! [`ManPage`] implements [`Display`], so it can be written to anything
+//! implementing [`fmt::Write`] (for example from a build script).
+//! If you compile with `derive`, [`Key::man_page`] creates it
+//! from the documentation comments and attributes.
+//!
+//! [`ManPage`]: struct.ManPage.html
+//! [`Display`]: https://doc.rust-lang.org/core/fmt/trait.Display.html
+//! [`fmt::Write`]: https://doc.rust-lang.org/core/fmt/trait.Write.html
+//! [`Key::man_page`]: ../trait.Key.html#method.man_page
+
+use core::fmt;
+
+/// A key in a man page.
+///
+/// See the [module documentation] for more details.
+///
+/// [module documentation]: index.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ManKey<'a> {
+    name: &'a str,
+    doc: &'a str,
+    value_name: Option<&'a str>,
+    default: Option<&'a str>,
+    env: Option<&'a str>,
+}
+
+impl<'a> ManKey<'a> {
+    /// Describe the key `name` (without the leading dash) with `doc`.
+    ///
+    /// Its value is called `value`.
+    #[must_use]
+    pub const fn new(name: &'a str, doc: &'a str) -> Self {
+        Self {
+            name,
+            doc,
+            value_name: Some("value"),
+            default: None,
+            env: None,
+        }
+    }
+
+    /// Set the placeholder for the value.
+    #[must_use]
+    pub const fn value_name(mut self, value_name: &'a str) -> Self {
+        self.value_name = Some(value_name);
+        self
+    }
+
+    /// Don't show a value, because the key doesn't take one.
+    #[must_use]
+    pub const fn flag(mut self) -> Self {
+        self.value_name = None;
+        self
+    }
+
+    /// Show the default value.
+    #[must_use]
+    pub const fn default(mut self, default: &'a str) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Show the environment variable that is used if the key doesn't occur.
+    #[must_use]
+    pub const fn env(mut self, var: &'a str) -> Self {
+        self.env = Some(var);
+        self
+    }
+
+    /// Write the entry of the `OPTIONS` section.
+    fn write<W>(&self, f: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        f.write_str(".TP\n\\fB\\-")?;
+        escape(self.name, f)?;
+        f.write_str("\\fR")?;
+        if let Some(value_name) = self.value_name {
+            f.write_str(" \\fI")?;
+            escape(value_name, f)?;
+            f.write_str("\\fR")?;
+        }
+        f.write_char('\n')?;
+        paragraphs(self.doc, ".IP", f)?;
+        if let Some(var) = self.env {
+            f.write_str(".IP\nEnvironment: ")?;
+            escape(var, f)?;
+            f.write_char('\n')?;
+        }
+        if let Some(default) = self.default {
+            f.write_str(".IP\nDefault: ")?;
+            escape(default, f)?;
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+/// A man page in roff.
+///
+/// See the [module documentation] for more details.
+///
+/// [module documentation]: index.html
+#[derive(Debug, Clone, Copy)]
+pub struct ManPage<'a> {
+    name: &'a str,
+    section: u8,
+    description: &'a str,
+    synopsis: &'a str,
+    keys: &'a [ManKey<'a>],
+}
+
+impl<'a> ManPage<'a> {
+    /// Create a man page for the program `name` with `keys`.
+    ///
+    /// It's in section 1 (user commands).
+    #[must_use]
+    pub const fn new(name: &'a str, keys: &'a [ManKey<'a>]) -> Self {
+        Self {
+            name,
+            section: 1,
+            description: "",
+            synopsis: "",
+            keys,
+        }
+    }
+
+    /// Put the man page into another section.
+    #[must_use]
+    pub const fn section(mut self, section: u8) -> Self {
+        self.section = section;
+        self
+    }
+
+    /// Describe the program.
+    #[must_use]
+    pub const fn description(mut self, description: &'a str) -> Self {
+        self.description = description;
+        self
+    }
+
+    /// Show the arguments (like a usage line without the name of the program).
+    #[must_use]
+    pub const fn synopsis(mut self, synopsis: &'a str) -> Self {
+        self.synopsis = synopsis;
+        self
+    }
+
+    /// Write the man page to `f`.
+    ///
+    /// # Errors
+    ///
+    /// This only fails if writing to `f` does.
+    pub fn write<W>(&self, f: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        f.write_str(".TH \"")?;
+        for c in self.name.chars().flat_map(char::to_uppercase) {
+            match c {
+                // this would end the quoted argument
+                '"' => f.write_str("\\(dq")?,
+                c => escape(c.encode_utf8(&mut [0; 4]), f)?,
+            }
+        }
+        write!(f, "\" \"{}\"\n.SH NAME\n", self.section)?;
+        escape(self.name, f)?;
+        let (short, long) = self
+            .description
+            .split_once('\n')
+            .unwrap_or((self.description, ""));
+        if !short.is_empty() {
+            f.write_str(" \\- ")?;
+            escape(short, f)?;
+        }
+        f.write_str("\n.SH SYNOPSIS\n.B ")?;
+        escape(self.name, f)?;
+        f.write_char('\n')?;
+        if !self.synopsis.is_empty() {
+            escape(self.synopsis, f)?;
+            f.write_char('\n')?;
+        }
+        if !long.is_empty() {
+            f.write_str(".SH DESCRIPTION\n")?;
+            paragraphs(long, ".PP", f)?;
+        }
+        if !self.keys.is_empty() {
+            f.write_str(".SH OPTIONS\n")?;
+            for key in self.keys {
+                key.write(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ManPage<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f)
+    }
+}
+
+/// Write the paragraphs of `text`, separated by the macro `separator`.
+fn paragraphs<W>(text: &str, separator: &str, f: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    if text.is_empty() {
+        return Ok(());
+    }
+    for (index, paragraph) in text.split('\n').enumerate() {
+        if index > 0 {
+            writeln!(f, "{separator}")?;
+        }
+        escape(paragraph, f)?;
+        f.write_char('\n')?;
+    }
+    Ok(())
+}
+
+/// Write `text` so that roff shows it as it is.
+fn escape<W>(text: &str, f: &mut W) -> fmt::Result
+where
+    W: fmt::Write,
+{
+    // this would be a request at the start of a line
+    if text.starts_with(['.', '\'']) {
+        f.write_str("\\&")?;
+    }
+    for c in text.chars() {
+        match c {
+            '\\' => f.write_str("\\e")?,
+            '-' => f.write_str("\\-")?,
+            c => f.write_char(c)?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,54 @@
+/// How often a key may occur.
+///
+/// This is used by [`ArgumentIterator::occurrences`].
+///
+/// [`ArgumentIterator::occurrences`]: struct.ArgumentIterator.html#method.occurrences
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Occurrences {
+    min: usize,
+    max: Option<usize>,
+}
+
+impl Occurrences {
+    /// The key may occur once or not at all.
+    pub const AT_MOST_ONCE: Self = Self::at_most(1);
+
+    /// The key has to occur exactly once.
+    pub const EXACTLY_ONCE: Self = Self::between(1, 1);
+
+    /// The key has to occur at least `min` times.
+    #[must_use]
+    pub const fn at_least(min: usize) -> Self {
+        Self { min, max: None }
+    }
+
+    /// The key may occur at most `max` times.
+    #[must_use]
+    pub const fn at_most(max: usize) -> Self {
+        Self {
+            min: 0,
+            max: Some(max),
+        }
+    }
+
+    /// The key has to occur at least `min` and at most `max` times.
+    #[must_use]
+    pub const fn between(min: usize, max: usize) -> Self {
+        Self {
+            min,
+            max: Some(max),
+        }
+    }
+
+    /// The minimum number of occurrences.
+    #[must_use]
+    pub const fn min(self) -> usize {
+        self.min
+    }
+
+    /// The maximum number of occurrences, if any.
+    #[must_use]
+    pub const fn max(self) -> Option<usize> {
+        self.max
+    }
+}
@@ -0,0 +1,256 @@
+//! Expands response files (`@file`) in the args.
+//!
+//! # Usage
+//!
+//! ```
+//! # use miniarg::response_files::ResponseFiles;
+//! # use miniarg::split_args::SplitArgs;
+//! let loader = |path: &str| (path == "args.rsp").then_some("-foo 'a value'\n-bar b");
+//! let args = ResponseFiles::new(SplitArgs::new("executable @args.rsp -baz c"), loader);
+//! assert_eq!(
+//!     miniarg::parse_from_iter(args, &["foo", "bar", "baz"])
+//!         .collect::<Result<Vec<_>, _>>()?,
+//!     vec![(&"foo", "a value"), (&"bar", "b"), (&"baz", "c")],
+//! );
+//! # Ok::<(), miniarg::ParseError<'static>>(())
+//! ```
+//!
+//! Every arg (but the first one, which is the name of the executable,
+//! unless [`ResponseFiles::without_program_name`] is used)
+//! that starts with `@` is replaced by the contents of the file.
+//! The contents are split with [`SplitArgs`], so they may span multiple lines.
+//! Response files can contain other response files,
+//! up to a depth of 8 (see [`ResponseFiles::with_max_depth`] to change this).
+//!
+//! The files are read by a [`Loader`]. This is implemented for closures,
+//! so that they can be read from anywhere (for example without `std`),
+//! and with `std` for [`FileLoader`], which reads them from the file system.
+//!
+//! Files that can't be loaded, cycles and too deeply nested response files
+//! are reported as errors (with the path) and the file is skipped.
+//!
+//! [`SplitArgs`]: ../split_args/struct.SplitArgs.html
+//! [`ResponseFiles::without_program_name`]: struct.ResponseFiles.html#method.without_program_name
+//! [`ResponseFiles::with_max_depth`]: struct.ResponseFiles.html#method.with_max_depth
+//! [`Loader`]: trait.Loader.html
+//! [`FileLoader`]: struct.FileLoader.html
+
+use core::iter::FusedIterator;
+
+use crate::split_args::SplitArgs;
+use crate::{Argument, ParseError};
+
+/// Loads the contents of response files.
+///
+/// See the [module documentation] for more details.
+///
+/// [module documentation]: index.html
+pub trait Loader<'a> {
+    /// Get the contents of the file at `path`, or `None` if it can't be loaded.
+    fn load(&mut self, path: &str) -> Option<&'a str>;
+}
+
+impl<'a, F> Loader<'a> for F
+where
+    F: FnMut(&str) -> Option<&'a str>,
+{
+    fn load(&mut self, path: &str) -> Option<&'a str> {
+        self(path)
+    }
+}
+
+/// Reads response files from the file system.
+///
+/// The contents are stored in buffers owned by the caller, one per file,
+/// so the returned args live as long as them.
+/// If all buffers are used, further files can't be loaded.
+///
+/// This requires the `std` feature.
+///
+/// ```no_run
+/// # use core::cell::OnceCell;
+/// # use miniarg::response_files::{FileLoader, ResponseFiles};
+/// # use miniarg::split_args::SplitArgs;
+/// let buffers: [OnceCell<String>; 8] = Default::default();
+/// let args = ResponseFiles::new(SplitArgs::new("executable @args.rsp"), FileLoader::new(&buffers));
+/// let args = miniarg::parse_from_iter(args, &["foo"]);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+pub struct FileLoader<'a> {
+    buffers: &'a [core::cell::OnceCell<String>],
+}
+
+#[cfg(feature = "std")]
+impl<'a> FileLoader<'a> {
+    /// Read response files into `buffers`.
+    #[must_use]
+    pub const fn new(buffers: &'a [core::cell::OnceCell<String>]) -> Self {
+        Self { buffers }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> Loader<'a> for FileLoader<'a> {
+    fn load(&mut self, path: &str) -> Option<&'a str> {
+        let buffer = self.buffers.iter().find(|buffer| buffer.get().is_none())?;
+        let contents = std::fs::read_to_string(path).ok()?;
+        Some(buffer.get_or_init(|| contents))
+    }
+}
+
+/// Expands response files in the args.
+///
+/// See the [module documentation] for more details.
+///
+/// [module documentation]: index.html
+pub struct ResponseFiles<'a, S, L, const N: usize = 8> {
+    args: S,
+    loader: L,
+    // the response files that are currently being expanded and their remaining args
+    files: [Option<(&'a str, SplitArgs<'a>)>; N],
+    depth: usize,
+    // whether the first arg still has to be passed through
+    first: bool,
+}
+
+impl<'a, S, L> ResponseFiles<'a, S, L>
+where
+    S: Iterator,
+    S::Item: Argument<'a, Str = &'a str>,
+    L: Loader<'a>,
+{
+    /// Expand the response files in `args` with `loader`.
+    ///
+    /// See the [module documentation] for more details.
+    ///
+    /// [module documentation]: index.html
+    pub fn new(args: S, loader: L) -> Self {
+        Self::with_max_depth(args, loader)
+    }
+}
+
+impl<'a, S, L, const N: usize> ResponseFiles<'a, S, L, N>
+where
+    S: Iterator,
+    S::Item: Argument<'a, Str = &'a str>,
+    L: Loader<'a>,
+{
+    /// Expand the response files in `args` with `loader`,
+    /// allowing them to be nested up to `N` levels deep.
+    ///
+    /// ```
+    /// # use miniarg::ParseError;
+    /// # use miniarg::response_files::ResponseFiles;
+    /// # use miniarg::split_args::SplitArgs;
+    /// let loader = |path: &str| Some(if path == "a" { "@b" } else { "value" });
+    /// let mut args = ResponseFiles::<_, _, 1>::with_max_depth(SplitArgs::new("executable @a"), loader);
+    /// assert_eq!(args.next(), Some(Ok("executable")));
+    /// assert_eq!(args.next(), Some(Err(ParseError::ResponseFilesTooDeep("b"))));
+    /// assert_eq!(args.next(), None);
+    /// ```
+    pub fn with_max_depth(args: S, loader: L) -> Self {
+        Self {
+            args,
+            loader,
+            files: core::array::from_fn(|_| None),
+            depth: 0,
+            first: true,
+        }
+    }
+
+    /// Expand the first arg, too.
+    ///
+    /// By default, it's the name of the executable and passed through as is.
+    /// Use this if there is none, like with [`ArgumentIterator::without_program_name`].
+    ///
+    /// This has to be called before iterating.
+    ///
+    /// ```
+    /// # use miniarg::response_files::ResponseFiles;
+    /// # use miniarg::split_args::SplitArgs;
+    /// let loader = |path: &str| (path == "args.rsp").then_some("-foo a");
+    /// let args = ResponseFiles::new(SplitArgs::new("@args.rsp"), loader).without_program_name();
+    /// assert_eq!(
+    ///     miniarg::parse_from_iter(args, &["foo"])
+    ///         .without_program_name()
+    ///         .collect::<Result<Vec<_>, _>>()?,
+    ///     vec![(&"foo", "a")],
+    /// );
+    /// # Ok::<(), miniarg::ParseError<'static>>(())
+    /// ```
+    ///
+    /// [`ArgumentIterator::without_program_name`]: ../struct.ArgumentIterator.html#method.without_program_name
+    #[must_use]
+    pub fn without_program_name(mut self) -> Self {
+        self.first = false;
+        self
+    }
+
+    /// Get the next arg of the innermost response file or of the original args.
+    fn next_arg(&mut self) -> Option<<Self as Iterator>::Item> {
+        while let Some(depth) = self.depth.checked_sub(1) {
+            if let Some((_, args)) = &mut self.files[depth]
+                && let Some(arg) = args.next()
+            {
+                return Some(Ok(arg));
+            }
+            self.files[depth] = None;
+            self.depth = depth;
+        }
+        Some(self.args.next()?.into_argument())
+    }
+
+    /// Start expanding the response file at `path`.
+    fn open(&mut self, path: &'a str) -> Result<(), ParseError<'a>> {
+        if self.files.iter().flatten().any(|(other, _)| *other == path) {
+            return Err(ParseError::ResponseFileCycle(path));
+        }
+        if self.depth == N {
+            return Err(ParseError::ResponseFilesTooDeep(path));
+        }
+        let contents = self
+            .loader
+            .load(path)
+            .ok_or(ParseError::UnreadableResponseFile(path))?;
+        self.files[self.depth] = Some((path, SplitArgs::new(contents)));
+        self.depth += 1;
+        Ok(())
+    }
+}
+
+impl<'a, S, L, const N: usize> Iterator for ResponseFiles<'a, S, L, N>
+where
+    S: Iterator,
+    S::Item: Argument<'a, Str = &'a str>,
+    L: Loader<'a>,
+{
+    type Item = Result<&'a str, ParseError<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let arg = self.next_arg()?;
+            if core::mem::take(&mut self.first) {
+                return Some(arg);
+            }
+            let arg = match arg {
+                Ok(arg) => arg,
+                Err(e) => return Some(Err(e)),
+            };
+            let Some(path) = arg.strip_prefix('@') else {
+                return Some(Ok(arg));
+            };
+            if let Err(e) = self.open(path) {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+impl<'a, S, L, const N: usize> FusedIterator for ResponseFiles<'a, S, L, N>
+where
+    S: FusedIterator,
+    S::Item: Argument<'a, Str = &'a str>,
+    L: Loader<'a>,
+{
+}
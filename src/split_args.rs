@@ -13,7 +13,20 @@
 //! ```
 //!
 //! It never panics or errors.
+//! Unterminated quotes just end at the end of the cmdline.
+//! If you'd rather report them, use [`TrySplitArgs`] instead:
+//!
+//! ```
+//! # use miniarg::split_args::{SplitError, TrySplitArgs};
+//! let mut args = TrySplitArgs::new("executable \"param1 param2");
+//! assert_eq!(args.next(), Some(Ok("executable")));
+//! assert_eq!(args.next(), Some(Err(SplitError::UnterminatedQuote { start: 11 })));
+//! assert_eq!(args.next(), None);
+//! ```
+//!
+//! [`TrySplitArgs`]: struct.TrySplitArgs.html
 
+use core::fmt;
 use core::iter::FusedIterator;
 
 use crate::parse::{Char, Quote, StrChars, StrIndex, StrRange};
@@ -51,10 +64,13 @@ impl<'a> SplitArgs<'a> {
     }
 }
 
-impl<'a> Iterator for SplitArgs<'a> {
-    type Item = &'a str;
-
-    fn next(&mut self) -> Option<Self::Item> {
+impl<'a> SplitArgs<'a> {
+    /// Get the next argument.
+    ///
+    /// If the argument is enclosed in quotes that aren't closed,
+    /// the position of the opening quote is returned as the error
+    /// together with the rest of the cmdline.
+    fn next_arg(&mut self) -> Option<Result<&'a str, (StrIndex, &'a str)>> {
         loop {
             let c = self.iter.peek()?;
 
@@ -79,17 +95,18 @@ impl<'a> Iterator for SplitArgs<'a> {
 
                                 // SAFETY: `start` and `end` are obtained via
                                 //         the iterator, so they must be valid.
-                                return Some(self.get_range(start, end));
+                                return Some(Ok(self.get_range(start, end)));
                             }
                         }
                     }
 
                     // SAFETY: `start` was obtained via the iterator, so this
                     //         range must be valid.
-                    return Some(self.get_range(start, self.iter.pos()));
+                    return Some(Ok(self.get_range(start, self.iter.pos())));
                 }
 
                 Char::Quote(Quote::Single) => {
+                    let quote = self.iter.pos();
                     self.iter.advance();
                     let start = self.iter.pos();
 
@@ -105,17 +122,18 @@ impl<'a> Iterator for SplitArgs<'a> {
 
                                 // SAFETY: `start` and `end` are obtained via
                                 //         the iterator, so they must be valid.
-                                return Some(self.get_range(start, end));
+                                return Some(Ok(self.get_range(start, end)));
                             }
                         }
                     }
 
                     // SAFETY: `start` was obtained via the iterator, so this
                     //         range must be valid.
-                    return Some(self.get_range(start, self.iter.pos()));
+                    return Some(Err((quote, self.get_range(start, self.iter.pos()))));
                 }
 
                 Char::Quote(Quote::Double) => {
+                    let quote = self.iter.pos();
                     self.iter.advance();
                     let start = self.iter.pos();
 
@@ -131,22 +149,93 @@ impl<'a> Iterator for SplitArgs<'a> {
 
                                 // SAFETY: `start` and `end` are obtained via
                                 //         the iterator, so they must be valid.
-                                return Some(self.get_range(start, end));
+                                return Some(Ok(self.get_range(start, end)));
                             }
                         }
                     }
 
                     // SAFETY: `start` was obtained via the iterator, so this
                     //         range must be valid.
-                    return Some(self.get_range(start, self.iter.pos()));
+                    return Some(Err((quote, self.get_range(start, self.iter.pos()))));
                 }
             }
         }
     }
 }
 
+impl<'a> Iterator for SplitArgs<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // unterminated quotes just end at the end of the cmdline
+        self.next_arg()
+            .map(|arg| arg.unwrap_or_else(|(_, rest)| rest))
+    }
+}
+
 impl FusedIterator for SplitArgs<'_> {}
 
+/// Splits a cmdline into multiple args, but reports unterminated quotes.
+///
+/// This works like [`SplitArgs`], but yields a [`SplitError`] instead of
+/// silently accepting a quote that is never closed.
+///
+/// See the [module documentation] for more details.
+///
+/// [`SplitArgs`]: struct.SplitArgs.html
+/// [`SplitError`]: enum.SplitError.html
+/// [module documentation]: index.html
+pub struct TrySplitArgs<'a> {
+    inner: SplitArgs<'a>,
+}
+
+impl<'a> TrySplitArgs<'a> {
+    /// Creates from a cmdline.
+    ///
+    /// See the [module documentation] for more details.
+    ///
+    /// [module documentation]: index.html
+    #[must_use]
+    pub const fn new(cmdline: &'a str) -> Self {
+        Self {
+            inner: SplitArgs::new(cmdline),
+        }
+    }
+}
+
+impl<'a> Iterator for TrySplitArgs<'a> {
+    type Item = Result<&'a str, SplitError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_arg().map(|arg| {
+            arg.map_err(|(start, _)| SplitError::UnterminatedQuote {
+                start: start.byte_index(),
+            })
+        })
+    }
+}
+
+impl FusedIterator for TrySplitArgs<'_> {}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[non_exhaustive]
+/// Errors occurred during splitting the cmdline.
+pub enum SplitError {
+    /// a quote was opened at byte offset `start`, but never closed
+    UnterminatedQuote { start: usize },
+}
+
+impl fmt::Display for SplitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Self::UnterminatedQuote { start } => {
+                write!(f, "the quote at offset {start} is never closed")
+            }
+        }
+    }
+}
+impl crate::Error for SplitError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -177,4 +266,23 @@ mod tests {
     test!(non_ascii_basic: "strÄng" => ["strÄng"]);
     test!(non_ascii_two: "sträng1 sträng2" => ["sträng1", "sträng2"]);
     test!(non_acsii_quotes: "\"sträng1 sträng2\"" => ["sträng1 sträng2"]);
+
+    macro_rules! try_test {
+        ($test:ident: $cmdline:expr => [ $($arg:expr),* ]) => {
+            #[test]
+            fn $test() {
+                let mut parsed = TrySplitArgs::new($cmdline);
+                $(
+                    assert_eq!(parsed.next(), Some($arg));
+                )*
+                assert_eq!(parsed.next(), None);
+            }
+        };
+    }
+
+    try_test!(try_basic: "string1 string2" => [Ok("string1"), Ok("string2")]);
+    try_test!(try_quotes: "'1 2' \"3 4\"" => [Ok("1 2"), Ok("3 4")]);
+    try_test!(try_unterminated_single_quotes: "1 '2 3 4" => [Ok("1"), Err(SplitError::UnterminatedQuote { start: 2 })]);
+    try_test!(try_unterminated_double_quotes: "1 \"2 3 4" => [Ok("1"), Err(SplitError::UnterminatedQuote { start: 2 })]);
+    try_test!(try_unterminated_non_ascii: "ä 'b" => [Ok("ä"), Err(SplitError::UnterminatedQuote { start: 3 })]);
 }
@@ -0,0 +1,251 @@
+use core::marker::PhantomData;
+
+#[cfg(feature = "std")]
+use crate::EnvArgs;
+use crate::split_args::SplitArgs;
+use crate::{ArgResult, Argument, ArgumentIterator, ParseError, ParseWarning};
+
+/// A layer of subcommands.
+///
+/// Derive this with an enum whose kinds each wrap an enum deriving [`Key`].
+/// The first argument (after the name of the executable, unless
+/// [`SubcommandIterator::without_program_name`] is used) selects the subcommand
+/// (the name of the kind, starting with lowercase) and the remaining ones
+/// are parsed with the keys of the wrapped enum.
+///
+/// The crate needs to be compiled with `derive` and either `std` or `alloc`.
+///
+/// # Example
+/// ```
+/// # use miniarg::*;
+/// # use std::fmt;
+/// #[derive(Debug, Key, PartialEq, Eq, Hash)]
+/// enum FlashKeys {
+///     /// the serial port
+///     Port,
+/// }
+///
+/// #[derive(Debug, Key, PartialEq, Eq, Hash)]
+/// enum DumpKeys {
+///     /// the start address
+///     Addr,
+/// }
+///
+/// #[derive(Debug, Subcommand, PartialEq, Eq, Hash)]
+/// enum Command {
+///     /// write the firmware
+///     Flash(FlashKeys),
+///     /// read the memory
+///     Dump(DumpKeys),
+/// }
+///
+/// let mut args = Command::parse("fwtool flash -port /dev/ttyUSB0");
+/// assert_eq!(args.next(), Some(Ok((Command::Flash(FlashKeys::Port), "/dev/ttyUSB0"))));
+/// assert_eq!(args.next(), None);
+/// assert_eq!(Command::help_text(), "flash\twrite the firmware\ndump\tread the memory");
+/// assert_eq!(Command::subcommand_help_text("dump"), Some("-addr\tthe start address"));
+/// ```
+///
+/// [`Key`]: trait.Key.html
+/// [`SubcommandIterator::without_program_name`]: struct.SubcommandIterator.html#method.without_program_name
+pub trait Subcommand {
+    /// Parse the cmdline.
+    ///
+    /// You'll get an iterator yielding subcommand value pairs.
+    fn parse(cmdline: &str) -> SubcommandIterator<'_, Self, SplitArgs<'_>>
+    where
+        Self: Sized,
+    {
+        Self::parse_from_iter(SplitArgs::new(cmdline))
+    }
+
+    /// Parse from a custom iterator.
+    ///
+    /// This is like [`parse_from_iter`], see there for details.
+    ///
+    /// [`parse_from_iter`]: fn.parse_from_iter.html
+    fn parse_from_iter<'a, S>(args: S) -> SubcommandIterator<'a, Self, S>
+    where
+        Self: Sized,
+        S: Iterator,
+        S::Item: Argument<'a>,
+    {
+        SubcommandIterator::new(args)
+    }
+
+    /// Parse the arguments of the current process.
+    ///
+    /// This is like [`parse_env`], see there for details.
+    ///
+    /// [`parse_env`]: fn.parse_env.html
+    #[cfg(feature = "std")]
+    fn parse_env() -> SubcommandIterator<'static, Self, EnvArgs>
+    where
+        Self: Sized,
+    {
+        Self::parse_from_iter(EnvArgs::new())
+    }
+
+    /// Get the names of the subcommands.
+    fn subcommands() -> &'static [&'static str];
+
+    /// Get the names of the keys of the subcommand at `index`.
+    fn keys(index: usize) -> &'static [&'static str];
+
+    /// Apply the constraints of the keys of the subcommand at `index`.
+    fn configure<'a, 'b, T, S>(
+        index: usize,
+        args: ArgumentIterator<'a, 'b, T, S>,
+    ) -> ArgumentIterator<'a, 'b, T, S>
+    where
+        T: crate::ToString,
+        S: Iterator,
+        S::Item: Argument<'a>;
+
+    /// Get the kind for the key `name` of the subcommand at `index`.
+    fn from_key(index: usize, name: &str) -> Option<Self>
+    where
+        Self: Sized;
+
+    /// Get a help text.
+    ///
+    /// This lists the subcommands and is being created from the enum kinds
+    /// and their documentation comments.
+    fn help_text() -> &'static str;
+
+    /// Get the help text of the subcommand `name`.
+    ///
+    /// This is the [`Key::help_text`] of the wrapped enum.
+    ///
+    /// [`Key::help_text`]: trait.Key.html#tymethod.help_text
+    fn subcommand_help_text(name: &str) -> Option<&'static str>;
+}
+
+/// The iterator returned by [`Subcommand::parse`] and [`Subcommand::parse_from_iter`].
+///
+/// [`Subcommand::parse`]: trait.Subcommand.html#method.parse
+/// [`Subcommand::parse_from_iter`]: trait.Subcommand.html#method.parse_from_iter
+pub struct SubcommandIterator<'a, T, S>
+where
+    S: Iterator,
+    S::Item: Argument<'a>,
+{
+    // the arguments until the subcommand is selected
+    args: Option<S>,
+    // the first argument (if it's not parsed)
+    program_name: Option<ArgResult<'a, S::Item>>,
+    // an argument to handle before the ones of `args`
+    pending: Option<ArgResult<'a, S::Item>>,
+    // the selected subcommand and its arguments
+    inner: Option<(usize, ArgumentIterator<'a, 'a, &'static str, S>)>,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T, S> SubcommandIterator<'a, T, S>
+where
+    T: Subcommand,
+    S: Iterator,
+    S::Item: Argument<'a>,
+{
+    fn new(mut args: S) -> Self {
+        // argv[0] is the name of the program,
+        // but errors have to be reported even there
+        let (program_name, pending) = match args.next().map(Argument::into_argument) {
+            Some(Err(e)) => (None, Some(Err(e))),
+            program_name => (program_name, None),
+        };
+        SubcommandIterator {
+            args: Some(args),
+            program_name,
+            pending,
+            inner: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Select the subcommand with the first argument, too.
+    ///
+    /// By default, the first argument is the name of the program (see [`program_name`]).
+    /// This is like [`ArgumentIterator::without_program_name`], see there for details.
+    ///
+    /// This has to be called before iterating.
+    ///
+    /// [`program_name`]: #method.program_name
+    /// [`ArgumentIterator::without_program_name`]: struct.ArgumentIterator.html#method.without_program_name
+    #[must_use]
+    pub fn without_program_name(mut self) -> Self {
+        if let Some(arg) = self.program_name.take() {
+            self.pending = Some(arg);
+        }
+        self
+    }
+
+    /// Get the name of the program (the first argument), if there is one.
+    #[must_use]
+    pub fn program_name(&self) -> Option<&str> {
+        self.program_name.as_ref()?.as_ref().ok().map(AsRef::as_ref)
+    }
+
+    /// Select the subcommand with the next argument.
+    fn select(
+        &mut self,
+        mut args: S,
+    ) -> Result<(), ParseError<'a, <S::Item as Argument<'a>>::Str>> {
+        let name = match self.pending.take() {
+            Some(arg) => arg,
+            None => args
+                .next()
+                .ok_or(ParseError::MissingSubcommand)?
+                .into_argument(),
+        }?;
+        let Some(index) = T::subcommands().iter().position(|s| *s == name.as_ref()) else {
+            return Err(ParseError::UnknownSubcommand(name));
+        };
+        let keys = T::keys(index);
+        self.inner = Some((
+            index,
+            T::configure(
+                index,
+                ArgumentIterator::new(args, keys).without_program_name(),
+            ),
+        ));
+        Ok(())
+    }
+
+    /// Get the warnings about the arguments of the subcommand parsed so far.
+    ///
+    /// See [`ArgumentIterator::warnings`] for details.
+    ///
+    /// [`ArgumentIterator::warnings`]: struct.ArgumentIterator.html#method.warnings
+    pub fn warnings(&self) -> impl Iterator<Item = ParseWarning<'a>> {
+        self.inner.iter().flat_map(|(_, inner)| inner.warnings())
+    }
+}
+
+impl<'a, T, S> Iterator for SubcommandIterator<'a, T, S>
+where
+    T: Subcommand,
+    S: Iterator,
+    S::Item: Argument<'a>,
+{
+    type Item =
+        Result<(T, <S::Item as Argument<'a>>::Str), ParseError<'a, <S::Item as Argument<'a>>::Str>>;
+
+    /// Get the next subcommand value pair or an error.
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(args) = self.args.take()
+            && let Err(e) = self.select(args)
+        {
+            return Some(Err(e));
+        }
+        let (index, inner) = self.inner.as_mut()?;
+        Some(
+            inner
+                .next()?
+                .and_then(|(name, value)| match T::from_key(*index, name) {
+                    Some(key) => Ok((key, value)),
+                    None => Err(ParseError::UnknownKey((*name).into())),
+                }),
+        )
+    }
+}
@@ -0,0 +1,34 @@
+//! A buffer for the unit tests.
+
+use core::fmt;
+
+/// A fixed size buffer, so that the tests also work without alloc.
+pub(crate) struct Buffer {
+    buf: [u8; 256],
+    len: usize,
+}
+
+impl Buffer {
+    pub(crate) fn new() -> Self {
+        Self {
+            buf: [0; 256],
+            len: 0,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.buf[..self.len]).unwrap()
+    }
+}
+
+impl fmt::Write for Buffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.buf
+            .get_mut(self.len..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
@@ -1,9 +1,12 @@
 //! Tests for the derive macro.
 #![cfg(feature = "derive")]
+// `&cmdline` also works if the cmdline is a `String`
+#![allow(clippy::needless_borrow)]
 
 use core::fmt;
 
-use miniarg::{ArgumentIterator, Key, ParseError};
+use miniarg::config::merge;
+use miniarg::{Key, ParseError, ParseOutcome, ParseWarning, Subcommand};
 
 #[derive(Debug, Key, PartialEq, Eq, Hash)]
 enum SimpleKeys {
@@ -17,6 +20,171 @@ enum SimpleKeys {
     Köy,
 }
 
+/// Copies files.
+///
+/// Existing files are
+/// overwritten.
+#[derive(Debug, Key, PartialEq, Eq, Hash)]
+enum DocumentedKeys {
+    /// the file to read,
+    ///   may be relative
+    ///
+    /// use `-` for stdin
+    Input,
+    /** the file to write */
+    Output,
+}
+
+#[derive(Debug, Key, PartialEq, Eq, Hash)]
+enum RequiredKeys {
+    /// the configuration file
+    #[miniarg(required)]
+    Config,
+    /// something optional
+    Optional,
+}
+
+#[derive(Debug, Key, PartialEq, Eq, Hash)]
+enum LimitedKeys {
+    /// may occur once
+    #[miniarg(once)]
+    Output,
+    /// has to occur two or three times
+    #[miniarg(min = 2, max = 3)]
+    Define,
+}
+
+#[derive(Debug, Key, PartialEq, Eq, Hash)]
+enum GroupedKeys {
+    /// read from a file
+    #[miniarg(conflicts_with = "stdin")]
+    File,
+    /// read from stdin
+    Stdin,
+    /// the private key
+    #[miniarg(requires = "cert")]
+    Key,
+    /// the certificate
+    Cert,
+}
+
+#[derive(Debug, Key, PartialEq, Eq, Hash)]
+enum DefaultKeys {
+    /// the host
+    Host,
+    /// the port
+    #[miniarg(default = "8080")]
+    Port,
+}
+
+#[derive(Debug, Key, PartialEq, Eq, Hash)]
+enum EnvKeys {
+    /// the host
+    #[miniarg(env = "MYAPP_HOST")]
+    Host,
+    /// the port
+    #[miniarg(env = "MYAPP_PORT", default = "8080")]
+    Port,
+}
+
+/// Talks to a device.
+#[doc = concat!("It has to be ", "connected.")]
+#[derive(Debug, Key, PartialEq, Eq, Hash)]
+enum MacroDocKeys {
+    /// the serial port
+    #[doc = concat!("(like ", "/dev/ttyUSB0)")]
+    Port,
+}
+
+#[derive(Debug, Key, PartialEq, Eq, Hash)]
+enum CertKeys {
+    /// the private key
+    #[miniarg(requires = "cert")]
+    Key,
+    /// the certificate
+    #[miniarg(env = "PROBE_CERT")]
+    Cert,
+}
+
+#[derive(Debug, Key, PartialEq, Eq, Hash)]
+enum FlashKeys {
+    /// the serial port
+    #[miniarg(required)]
+    Port,
+}
+
+#[derive(Debug, Key, PartialEq, Eq, Hash)]
+enum DumpKeys {
+    /// the start address
+    Addr,
+    /// the number of bytes
+    #[miniarg(default = "16")]
+    Len,
+}
+
+#[derive(Debug, Subcommand, PartialEq, Eq, Hash)]
+enum Command {
+    /// write the firmware
+    Flash(FlashKeys),
+    /// read the memory
+    Dump(DumpKeys),
+}
+
+#[derive(Debug, Key, PartialEq, Eq, Hash)]
+enum FlagKeys {
+    /// colorize the output
+    #[miniarg(flag)]
+    Color,
+    /// the output file
+    Output,
+}
+
+#[derive(Debug, Key, PartialEq, Eq, Hash)]
+enum HyphenKeys {
+    /// the offset
+    Offset,
+    /// arguments for the child process
+    #[miniarg(greedy, allow_hyphen_values)]
+    Args,
+}
+
+#[derive(Debug, Key, PartialEq, Eq, Hash)]
+enum UsageKeys {
+    /// more output
+    #[miniarg(flag)]
+    Verbose,
+    /// the config file
+    #[miniarg(required, once, value_name = "FILE")]
+    Config,
+    /// preprocessor definitions
+    Define,
+    /// the start and end address
+    #[miniarg(arity = 2, max = 1, value_name = "ADDR")]
+    Range,
+}
+
+#[derive(Debug, Key, PartialEq, Eq, Hash)]
+enum RenamedKeys {
+    /// the output file
+    Output,
+    /// the output file
+    #[miniarg(hidden, deprecated = "use -output")]
+    Out,
+    /// for debugging
+    #[miniarg(hidden)]
+    Trace,
+}
+
+#[derive(Debug, Key, PartialEq, Eq, Hash)]
+enum ArityKeys {
+    /// the start and end address
+    #[miniarg(arity = 2)]
+    Range,
+    /// preprocessor definitions
+    #[miniarg(greedy)]
+    Define,
+}
+
 #[test]
 /// Just calling a binary should produce an empty result.
 fn basic() {
@@ -102,12 +270,542 @@ fn missing_key() {
     );
 }
 
+#[test]
+/// A custom iterator should work, too.
+fn from_iter() {
+    let args = vec!["executable", "-key1", "value1", "-key2", "value2"];
+    assert_eq!(
+        SimpleKeys::parse_from_iter(args.into_iter())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(&SimpleKeys::Key1, "value1"), (&SimpleKeys::Key2, "value2")]
+    );
+}
+
+#[test]
+/// Owned arguments should work, too.
+fn from_owned_iter() {
+    let args = vec![
+        String::from("executable"),
+        String::from("-key"),
+        String::from("value"),
+    ];
+    assert_eq!(
+        SimpleKeys::parse_from_iter(args.into_iter())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(&SimpleKeys::Key, String::from("value"))]
+    );
+}
+
+#[test]
+/// A required key should be accepted.
+fn required() {
+    let cmdline = "executable -config file";
+    assert_eq!(
+        RequiredKeys::parse(cmdline)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(&RequiredKeys::Config, "file")]
+    );
+}
+
+#[test]
+/// A missing required key should produce an error.
+fn missing_required() {
+    let cmdline = "executable -optional value";
+    assert_eq!(
+        RequiredKeys::parse(cmdline)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err(),
+        ParseError::MissingKey("config")
+    );
+}
+
+#[test]
+/// Occurrence limits should be enforced.
+fn occurrences() {
+    assert_eq!(
+        LimitedKeys::parse("executable -define a -output b -define c")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![
+            (&LimitedKeys::Define, "a"),
+            (&LimitedKeys::Output, "b"),
+            (&LimitedKeys::Define, "c")
+        ]
+    );
+    assert_eq!(
+        LimitedKeys::parse("executable -define a -define b -output c -output d")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err(),
+        ParseError::DuplicateKey("output")
+    );
+    assert_eq!(
+        LimitedKeys::parse("executable -define a")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err(),
+        ParseError::TooFewOccurrences {
+            key: "define",
+            min: 2,
+            got: 1
+        }
+    );
+}
+
+#[test]
+/// Conflicts and dependencies should be checked.
+fn groups() {
+    assert_eq!(
+        GroupedKeys::parse("executable -file a -key b -cert c")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![
+            (&GroupedKeys::File, "a"),
+            (&GroupedKeys::Key, "b"),
+            (&GroupedKeys::Cert, "c")
+        ]
+    );
+    assert_eq!(
+        GroupedKeys::parse("executable -file a -stdin x")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err(),
+        ParseError::Conflict {
+            key: "file",
+            conflicts_with: "stdin"
+        }
+    );
+    assert_eq!(
+        GroupedKeys::parse("executable -key b")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err(),
+        ParseError::MissingDependency {
+            key: "key",
+            requires: "cert"
+        }
+    );
+}
+
+#[test]
+/// Absent keys should get their default values.
+fn defaults() {
+    assert_eq!(
+        DefaultKeys::parse("executable -host example.com")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![
+            (&DefaultKeys::Host, "example.com"),
+            (&DefaultKeys::Port, "8080")
+        ]
+    );
+    assert_eq!(
+        DefaultKeys::parse("executable -port 80")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(&DefaultKeys::Port, "80")]
+    );
+    assert_eq!(
+        DefaultKeys::help_text(),
+        "-host\tthe host\n-port\tthe port [default: 8080]"
+    );
+}
+
+#[test]
+/// Absent keys should be taken from the environment.
+fn env() {
+    let env = |name: &str| (name == "MYAPP_PORT").then_some("80");
+    assert_eq!(
+        EnvKeys::parse("executable -host example.com")
+            .env_source(&env)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(&EnvKeys::Host, "example.com"), (&EnvKeys::Port, "80")]
+    );
+    assert_eq!(
+        EnvKeys::parse("executable -port 443")
+            .env_source(&env)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(&EnvKeys::Port, "443")]
+    );
+    assert_eq!(
+        EnvKeys::help_text(),
+        "-host\tthe host [env: MYAPP_HOST]\n-port\tthe port [env: MYAPP_PORT] [default: 8080]"
+    );
+}
+
+#[test]
+/// Documentation that isn't a literal should be skipped.
+fn macro_doc() {
+    assert_eq!(
+        MacroDocKeys::help_text(),
+        "Talks to a device.\n\n-port\tthe serial port"
+    );
+}
+
+#[test]
+/// Keys taken from the environment should count as present.
+fn env_is_present() {
+    let env = |name: &str| (name == "PROBE_CERT").then_some("c");
+    assert_eq!(
+        CertKeys::parse("executable -key k")
+            .env_source(&env)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(&CertKeys::Key, "k"), (&CertKeys::Cert, "c")]
+    );
+}
+
+#[test]
+/// Config files should use the same keys as the cmdline.
+fn config() {
+    let file = DefaultKeys::parse_config("# defaults\nhost example.com\nport = 8080")
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        file,
+        vec![
+            (&DefaultKeys::Host, "example.com"),
+            (&DefaultKeys::Port, "8080")
+        ]
+    );
+    let cmdline = DefaultKeys::parse("executable -port 80")
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        merge(&file, &cmdline).collect::<Vec<_>>(),
+        vec![
+            &(&DefaultKeys::Host, "example.com"),
+            &(&DefaultKeys::Port, "80")
+        ]
+    );
+}
+
+#[test]
+/// The first argument should select the subcommand and its keys.
+fn subcommands() {
+    assert_eq!(
+        Command::parse("fwtool flash -port /dev/ttyUSB0")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(Command::Flash(FlashKeys::Port), "/dev/ttyUSB0")]
+    );
+    assert_eq!(
+        Command::parse("fwtool dump -addr 0x1000")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![
+            (Command::Dump(DumpKeys::Addr), "0x1000"),
+            (Command::Dump(DumpKeys::Len), "16")
+        ]
+    );
+}
+
+#[test]
+/// The name of the program should be available and skipping it optional.
+fn subcommand_program_name() {
+    assert_eq!(
+        Command::parse("fwtool flash").program_name(),
+        Some("fwtool")
+    );
+    let args = Command::parse("flash -port /dev/ttyUSB0").without_program_name();
+    assert_eq!(args.program_name(), None);
+    assert_eq!(
+        args.collect::<Result<Vec<_>, _>>().unwrap(),
+        vec![(Command::Flash(FlashKeys::Port), "/dev/ttyUSB0")]
+    );
+}
+
+#[test]
+/// Missing or unknown subcommands and keys of other subcommands should produce errors.
+fn subcommand_errors() {
+    assert_eq!(
+        Command::parse("fwtool")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err(),
+        ParseError::MissingSubcommand
+    );
+    assert_eq!(
+        Command::parse("fwtool erase")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err(),
+        ParseError::UnknownSubcommand("erase")
+    );
+    assert_eq!(
+        Command::parse("fwtool dump -port x")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err(),
+        ParseError::UnknownKey("port")
+    );
+    assert_eq!(
+        Command::parse("fwtool flash")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err(),
+        ParseError::MissingKey("port")
+    );
+}
+
+#[test]
+/// There should be help texts for the subcommands and for each of them.
+fn subcommand_help_text() {
+    assert_eq!(
+        Command::help_text(),
+        "flash\twrite the firmware\ndump\tread the memory"
+    );
+    assert_eq!(
+        Command::subcommand_help_text("dump"),
+        Some("-addr\tthe start address\n-len\tthe number of bytes [default: 16]")
+    );
+    assert_eq!(Command::subcommand_help_text("erase"), None);
+}
+
+#[test]
+/// Flags shouldn't take a value and should be negatable.
+fn flags() {
+    assert_eq!(
+        FlagKeys::parse("executable -color -output file -no-color")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![
+            (&FlagKeys::Color, "true"),
+            (&FlagKeys::Output, "file"),
+            (&FlagKeys::Color, "false")
+        ]
+    );
+    assert_eq!(
+        FlagKeys::parse_config("color\noutput file\nno-color")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![
+            (&FlagKeys::Color, "true"),
+            (&FlagKeys::Output, "file"),
+            (&FlagKeys::Color, "false")
+        ]
+    );
+}
+
+#[test]
+/// Keys should take the declared number of values.
+fn arity() {
+    assert_eq!(
+        ArityKeys::parse("executable -range 0x1000 0x2000 -define A B C")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![
+            (&ArityKeys::Range, "0x1000"),
+            (&ArityKeys::Range, "0x2000"),
+            (&ArityKeys::Define, "A"),
+            (&ArityKeys::Define, "B"),
+            (&ArityKeys::Define, "C")
+        ]
+    );
+    assert_eq!(
+        ArityKeys::parse("executable -range 0x1000")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err(),
+        ParseError::MissingValue {
+            key: "range",
+            expected: 2,
+            got: 1
+        }
+    );
+}
+
+#[test]
+/// Values may start with a dash.
+fn hyphen_values() {
+    assert_eq!(
+        HyphenKeys::parse("executable -offset -16 -args -v -offset 1")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![
+            (&HyphenKeys::Offset, "-16"),
+            (&HyphenKeys::Args, "-v"),
+            (&HyphenKeys::Args, "-offset"),
+            (&HyphenKeys::Args, "1")
+        ]
+    );
+}
+
+#[test]
+/// Hidden and deprecated keys should still work.
+fn hidden_deprecated() {
+    let mut args = RenamedKeys::parse("executable -output a -trace b");
+    assert_eq!(
+        args.by_ref().collect::<Result<Vec<_>, _>>().unwrap(),
+        vec![(&RenamedKeys::Output, "a"), (&RenamedKeys::Trace, "b")]
+    );
+    assert_eq!(args.warnings().next(), None);
+    let mut args = RenamedKeys::parse("executable -out a");
+    assert_eq!(
+        args.by_ref().collect::<Result<Vec<_>, _>>().unwrap(),
+        vec![(&RenamedKeys::Out, "a")]
+    );
+    let warnings: Vec<_> = args.warnings().collect();
+    assert_eq!(
+        warnings,
+        vec![ParseWarning::Deprecated {
+            key: "out",
+            note: "use -output"
+        }]
+    );
+    assert_eq!(
+        warnings[0].to_string(),
+        "the key 'out' is deprecated: use -output"
+    );
+    assert_eq!(RenamedKeys::help_text(), "-output\tthe output file");
+    assert_eq!(
+        RenamedKeys::help_entries(),
+        &[("output", "the output file")]
+    );
+    assert_eq!(RenamedKeys::usage_args(), "[-output <value>...]");
+}
+
+#[test]
+/// `-help` and `-version` should work without being declared.
+fn builtins() {
+    let mut args = DefaultKeys::parse("executable -host a -help -port 1").builtins();
+    assert!(matches!(
+        args.next(),
+        Some(Ok(ParseOutcome::Arg((&DefaultKeys::Host, "a"))))
+    ));
+    let Some(Ok(ParseOutcome::Help(help))) = args.next() else {
+        panic!("expected the help");
+    };
+    assert_eq!(help.to_string(), DefaultKeys::help().to_string());
+    assert!(args.next().is_none());
+
+    let mut args = DefaultKeys::parse("executable -version").builtins();
+    let Some(Ok(ParseOutcome::Version(version))) = args.next() else {
+        panic!("expected the version");
+    };
+    assert_eq!(version, concat!("miniarg ", env!("CARGO_PKG_VERSION")));
+    assert!(args.next().is_none());
+
+    let mut args = DefaultKeys::parse("executable -unknown").builtins();
+    assert!(matches!(
+        args.next(),
+        Some(Err(ParseError::UnknownKey("unknown")))
+    ));
+}
+
+#[test]
+/// A man page should be generated.
+fn man_page() {
+    assert_eq!(
+        DocumentedKeys::man_page().to_string(),
+        r#".TH "MINIARG" "1"
+.SH NAME
+miniarg \- Copies files.
+.SH SYNOPSIS
+.B miniarg
+[\-input <value>...] [\-output <value>...]
+.SH DESCRIPTION
+Existing files are overwritten.
+.SH OPTIONS
+.TP
+\fB\-input\fR \fIvalue\fR
+the file to read, may be relative
+.IP
+use `\-` for stdin
+.TP
+\fB\-output\fR \fIvalue\fR
+the file to write
+"#
+    );
+    assert_eq!(
+        EnvKeys::man_page().to_string(),
+        r#".TH "MINIARG" "1"
+.SH NAME
+miniarg
+.SH SYNOPSIS
+.B miniarg
+[\-host <value>...] [\-port <value>...]
+.SH OPTIONS
+.TP
+\fB\-host\fR \fIvalue\fR
+the host
+.IP
+Environment: MYAPP_HOST
+.TP
+\fB\-port\fR \fIvalue\fR
+the port
+.IP
+Environment: MYAPP_PORT
+.IP
+Default: 8080
+"#
+    );
+    let page = RenamedKeys::man_page().to_string();
+    assert!(!page.contains("\\-out\\fR"));
+    assert!(!page.contains("trace"));
+    let page = UsageKeys::man_page().to_string();
+    assert!(page.contains("\\fB\\-verbose\\fR\n"));
+    assert!(page.contains("\\fB\\-range\\fR \\fIADDR ADDR\\fR\n"));
+}
+
+#[test]
+/// A usage line should be generated.
+fn usage() {
+    assert_eq!(
+        UsageKeys::usage_args(),
+        "[-verbose] -config <FILE> [-define <value>...] [-range <ADDR> <ADDR>]"
+    );
+    assert_eq!(
+        UsageKeys::usage(),
+        "usage: miniarg [-verbose] -config <FILE> [-define <value>...] [-range <ADDR> <ADDR>]"
+    );
+    assert_eq!(
+        ArityKeys::usage_args(),
+        "[-range <value> <value>...] [-define <value>...]"
+    );
+    let args = UsageKeys::parse("./tool -config a");
+    let mut usage = String::new();
+    UsageKeys::write_usage(args.program_name().unwrap(), &mut usage).unwrap();
+    assert_eq!(
+        usage,
+        "usage: ./tool [-verbose] -config <FILE> [-define <value>...] [-range <ADDR> <ADDR>]"
+    );
+}
+
 #[test]
 /// A help string should be generated.
 fn help_text() {
     assert_eq!(
         SimpleKeys::help_text(),
-        "-key\t first key\n-key1\t second key\n-key2\t\n-köy\t Th€ döcüm€ntätiön änd th€ k€y häv€ nön-äscii chärs."
+        "-key\tfirst key\n-key1\tsecond key\n-key2\t\n-köy\tTh€ döcüm€ntätiön änd th€ k€y häv€ nön-äscii chärs."
+    );
+}
+
+#[test]
+/// Documentation spanning multiple lines and on the enum itself should be kept.
+fn help_text_documentation() {
+    assert_eq!(
+        DocumentedKeys::help_text(),
+        "Copies files.\nExisting files are overwritten.\n\n\
+        -input\tthe file to read, may be relative\n\tuse `-` for stdin\n\
+        -output\tthe file to write"
+    );
+}
+
+#[test]
+/// The help text should be aligned and wrapped.
+fn help() {
+    assert_eq!(
+        DocumentedKeys::help().width(40).to_string(),
+        "\
+Copies files.
+Existing files are overwritten.
+
+  -input   the file to read, may be
+           relative
+           use `-` for stdin
+  -output  the file to write
+"
+    );
+    assert_eq!(
+        DefaultKeys::help().to_string(),
+        "  -host  the host\n  -port  the port [default: 8080]\n"
     );
 }
 
@@ -1,11 +1,13 @@
 //! The main file for integration tests.
 #![cfg_attr(not(feature = "std"), no_std)]
 #![cfg(any(feature = "alloc", feature = "std"))]
+// `&cmdline` also works if the cmdline is a `String`
+#![allow(clippy::needless_borrow)]
 
 extern crate alloc;
-use alloc::{vec, vec::Vec};
+use alloc::{string::String, vec, vec::Vec};
 
-use miniarg::{ParseError, parse};
+use miniarg::{Occurrences, ParseError, parse, parse_from_iter, parse_strict};
 
 #[test]
 /// Just calling a binary should produce an empty result.
@@ -225,3 +227,468 @@ fn ends_inside_double_quotes() {
         vec![(&"value", "test value")]
     );
 }
+
+#[test]
+fn strict_quotes() {
+    let cmdline = "executable -value 'test value'";
+    assert_eq!(
+        parse_strict(cmdline, &["value"])
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(&"value", "test value")]
+    );
+}
+
+#[test]
+fn strict_ends_inside_single_quotes() {
+    let cmdline = "executable -value 'test value";
+    assert_eq!(
+        parse_strict(cmdline, &["value"])
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err(),
+        ParseError::UnterminatedQuote { start: 18 }
+    );
+}
+
+#[test]
+fn strict_ends_inside_double_quotes() {
+    let cmdline = "executable -value \"test value";
+    assert_eq!(
+        parse_strict(cmdline, &["value"])
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err(),
+        ParseError::UnterminatedQuote { start: 18 }
+    );
+}
+
+#[test]
+/// Errors of the underlying iterator should be passed through.
+fn iter_error() {
+    let args = vec![
+        Ok("executable"),
+        Ok("-key"),
+        Err(ParseError::NotUnicode("v�lue")),
+    ];
+    assert_eq!(
+        parse_from_iter(args.into_iter(), &["key"])
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err(),
+        ParseError::NotUnicode("v�lue")
+    );
+}
+
+#[test]
+#[cfg(feature = "std")]
+/// The process arguments should be the same as the ones from `std`.
+fn env_args() {
+    assert!(
+        miniarg::EnvArgs::new()
+            .map(Result::unwrap)
+            .eq(std::env::args())
+    );
+}
+
+#[test]
+/// Owned arguments should produce owned values.
+fn owned_values() {
+    let args = vec![
+        String::from("executable"),
+        String::from("-key"),
+        String::from("value"),
+    ];
+    let parsed = parse_from_iter(args.into_iter(), &["key"])
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(parsed, vec![(&"key", String::from("value"))]);
+}
+
+#[test]
+/// Owned arguments should produce owned errors.
+fn owned_error() {
+    let args = vec![String::from("executable"), String::from("-invalid")];
+    let error = parse_from_iter(args.into_iter(), &["key"])
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_err();
+    assert_eq!(error, ParseError::UnknownKey(String::from("invalid")));
+}
+
+#[test]
+/// A trailing key with owned arguments should produce an empty string.
+fn owned_just_key() {
+    let args = vec![String::from("executable"), String::from("-key")];
+    assert_eq!(
+        parse_from_iter(args.into_iter(), &["key"])
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(&"key", String::new())]
+    );
+}
+
+#[test]
+/// An owned error should be the same as the borrowed one.
+fn into_owned() {
+    let cmdline = String::from("executable -invalid");
+    let error = parse(&cmdline, &["key"])
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_err()
+        .into_owned();
+    drop(cmdline);
+    assert_eq!(error, ParseError::UnknownKey(String::from("invalid")));
+}
+
+#[test]
+#[cfg(feature = "std")]
+/// Owned errors should work with `?` and `Box<dyn Error>`.
+fn owned_boxed_error() {
+    fn parse_owned(cmdline: &str) -> Result<(), std::boxed::Box<dyn std::error::Error>> {
+        parse(cmdline, &["key"])
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ParseError::into_owned)?;
+        Ok(())
+    }
+    assert_eq!(
+        parse_owned("executable -invalid").unwrap_err().to_string(),
+        "'invalid' is not a known key"
+    );
+}
+
+#[test]
+/// A required key that occurs should be fine.
+fn required_key() {
+    let cmdline = "executable -key value";
+    assert_eq!(
+        parse(cmdline, &["key", "other"])
+            .required(&["key"])
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(&"key", "value")]
+    );
+}
+
+#[test]
+/// Every missing required key should produce an error.
+fn missing_required_keys() {
+    let cmdline = "executable -key value";
+    assert_eq!(
+        parse(cmdline, &["key", "key1", "key2"])
+            .required(&["key1", "key", "key2"])
+            .filter_map(Result::err)
+            .collect::<Vec<_>>(),
+        vec![
+            ParseError::MissingKey("key1"),
+            ParseError::MissingKey("key2")
+        ]
+    );
+}
+
+#[test]
+/// A trailing required key should be yielded before the errors.
+fn required_just_key() {
+    let cmdline = "executable -key";
+    assert_eq!(
+        parse(cmdline, &["key", "other"])
+            .required(&["key", "other"])
+            .collect::<Vec<_>>(),
+        vec![Ok((&"key", "")), Err(ParseError::MissingKey("other"))]
+    );
+}
+
+#[test]
+/// A key that may only occur once should produce an error on the second occurrence.
+fn duplicate_key() {
+    let cmdline = "executable -output a -key b -output c -key d";
+    assert_eq!(
+        parse(cmdline, &["output", "key"])
+            .occurrences(&[("output", Occurrences::AT_MOST_ONCE)])
+            .collect::<Vec<_>>(),
+        vec![
+            Ok((&"output", "a")),
+            Ok((&"key", "b")),
+            Err(ParseError::DuplicateKey("output")),
+            Ok((&"key", "d")),
+        ]
+    );
+}
+
+#[test]
+/// A key that has to occur exactly once should produce errors if missing or duplicated.
+fn exactly_once() {
+    let options = &["output"];
+    let limits = &[("output", Occurrences::EXACTLY_ONCE)];
+    assert_eq!(
+        parse("executable", options)
+            .occurrences(limits)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err(),
+        ParseError::MissingKey("output")
+    );
+    assert_eq!(
+        parse("executable -output a -output b", options)
+            .occurrences(limits)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err(),
+        ParseError::DuplicateKey("output")
+    );
+}
+
+#[test]
+/// Keys with limits should be counted.
+fn occurrence_limits() {
+    let options = &["define", "lib"];
+    let limits = &[
+        ("define", Occurrences::at_least(2)),
+        ("lib", Occurrences::at_most(2)),
+    ];
+    assert_eq!(
+        parse("executable -define a -lib x -define b -lib y", options)
+            .occurrences(limits)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![
+            (&"define", "a"),
+            (&"lib", "x"),
+            (&"define", "b"),
+            (&"lib", "y")
+        ]
+    );
+    assert_eq!(
+        parse("executable -define a -lib x -lib y -lib z", options)
+            .occurrences(limits)
+            .filter_map(Result::err)
+            .collect::<Vec<_>>(),
+        vec![
+            ParseError::DuplicateKey("lib"),
+            ParseError::TooFewOccurrences {
+                key: "define",
+                min: 2,
+                got: 1
+            },
+        ]
+    );
+}
+
+#[test]
+/// Conflicting keys should only produce an error if both occur.
+fn conflicts() {
+    let options = &["file", "stdin"];
+    let conflicts = &[("file", "stdin")];
+    assert_eq!(
+        parse("executable -file a", options)
+            .conflicts(conflicts)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(&"file", "a")]
+    );
+    assert_eq!(
+        parse("executable -stdin x -file a", options)
+            .conflicts(conflicts)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err(),
+        ParseError::Conflict {
+            key: "file",
+            conflicts_with: "stdin"
+        }
+    );
+}
+
+#[test]
+/// A key should produce an error if its dependency is missing.
+fn requires() {
+    let options = &["key", "cert"];
+    let requires = &[("key", "cert")];
+    assert_eq!(
+        parse("executable -key a -cert b", options)
+            .requires(requires)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(&"key", "a"), (&"cert", "b")]
+    );
+    assert_eq!(
+        parse("executable -cert b", options)
+            .requires(requires)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(&"cert", "b")]
+    );
+    assert_eq!(
+        parse("executable -key a", options)
+            .requires(requires)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err(),
+        ParseError::MissingDependency {
+            key: "key",
+            requires: "cert"
+        }
+    );
+}
+
+#[test]
+/// All checks after the last argument should be reported in order.
+fn multiple_checks() {
+    let cmdline = "executable -file a -stdin x -key b";
+    assert_eq!(
+        parse(cmdline, &["file", "stdin", "key", "cert", "config"])
+            .required(&["config"])
+            .conflicts(&[("file", "stdin")])
+            .requires(&[("key", "cert")])
+            .filter_map(Result::err)
+            .collect::<Vec<_>>(),
+        vec![
+            ParseError::MissingKey("config"),
+            ParseError::Conflict {
+                key: "file",
+                conflicts_with: "stdin"
+            },
+            ParseError::MissingDependency {
+                key: "key",
+                requires: "cert"
+            },
+        ]
+    );
+}
+
+#[test]
+/// Defaults should work with owned arguments.
+fn defaults_owned() {
+    let args = vec![String::from("executable")];
+    assert_eq!(
+        parse_from_iter(args.into_iter(), &["port"])
+            .defaults(&[("port", "8080")])
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(&"port", String::from("8080"))]
+    );
+}
+
+#[test]
+#[cfg(feature = "std")]
+/// By default, keys should be taken from the environment of the process.
+fn process_env() {
+    // cargo sets this when running the tests
+    assert_eq!(
+        parse("executable", &["name"])
+            .env(&[("name", "CARGO_PKG_NAME")])
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(&"name", env!("CARGO_PKG_NAME"))]
+    );
+}
+
+#[test]
+/// Conflicts and missing dependencies should have a message.
+fn group_error_messages() {
+    use alloc::string::ToString;
+    let conflict = ParseError::Conflict {
+        key: "file",
+        conflicts_with: "stdin",
+    };
+    assert_eq!(
+        conflict.to_string(),
+        "the keys 'file' and 'stdin' can't be used together"
+    );
+    let dependency = ParseError::MissingDependency {
+        key: "key",
+        requires: "cert",
+    };
+    assert_eq!(
+        dependency.to_string(),
+        "the key 'key' requires the key 'cert'"
+    );
+}
+
+#[test]
+#[cfg(feature = "std")]
+/// Response files should be read from the file system.
+fn file_loader() {
+    use miniarg::response_files::{FileLoader, ResponseFiles};
+    use miniarg::split_args::SplitArgs;
+    let path = std::env::temp_dir().join(format!("miniarg-{}.rsp", std::process::id()));
+    std::fs::write(&path, "-key 'a value'\n").unwrap();
+    let cmdline = format!("executable @{0} @{0}", path.display());
+    let buffers: [core::cell::OnceCell<String>; 1] = Default::default();
+    let result = parse_from_iter(
+        ResponseFiles::new(SplitArgs::new(&cmdline), FileLoader::new(&buffers)),
+        &["key"],
+    )
+    .collect::<Vec<_>>();
+    std::fs::remove_file(&path).unwrap();
+    // there is only a buffer for the first file
+    assert_eq!(
+        result,
+        vec![
+            Ok((&"key", "a value")),
+            Err(ParseError::UnreadableResponseFile(path.to_str().unwrap()))
+        ]
+    );
+}
+
+#[test]
+/// The program name should be available for owned arguments, too.
+fn owned_program_name() {
+    let args = vec![String::from("executable"), String::from("-key")];
+    let iter = parse_from_iter(args.into_iter(), &["key"]);
+    assert_eq!(iter.program_name(), Some("executable"));
+}
+
+#[test]
+/// Checks on options that can't be tracked should be reported.
+fn untracked_conflicts() {
+    use alloc::format;
+    let options: Vec<String> = (0..130).map(|index| format!("k{index}")).collect();
+    let items: Vec<_> = parse("executable -k128 a -k129 b", &options)
+        .conflicts(&[("k128", "k129")])
+        .collect();
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[2], Err(ParseError::UntrackedKey("k128")));
+}
+
+#[test]
+/// Defaults should count as present for the other checks.
+fn defaults_are_present() {
+    let options = &["key", "cert"];
+    assert_eq!(
+        parse("executable -key k", options)
+            .defaults(&[("cert", "x")])
+            .requires(&[("key", "cert")])
+            .occurrences(&[("cert", Occurrences::EXACTLY_ONCE)])
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(&"key", "k"), (&"cert", "x")]
+    );
+    assert_eq!(
+        parse("executable -key k", options)
+            .defaults(&[("cert", "x"), ("cert", "y")])
+            .conflicts(&[("key", "cert")])
+            .collect::<Vec<_>>(),
+        vec![
+            Ok((&"key", "k")),
+            Ok((&"cert", "x")),
+            Err(ParseError::Conflict {
+                key: "key",
+                conflicts_with: "cert"
+            })
+        ]
+    );
+}
+
+#[test]
+/// Defaults for options that can't be tracked should be reported.
+fn untracked_defaults() {
+    use alloc::format;
+    let options: Vec<String> = (0..130).map(|index| format!("k{index}")).collect();
+    assert_eq!(
+        parse("executable", &options)
+            .defaults(&[("k129", "d")])
+            .collect::<Vec<_>>(),
+        vec![Err(ParseError::UntrackedKey("k129"))]
+    );
+}
+
+#[test]
+/// Quotes in the name shouldn't end the title of a man page.
+fn man_page_title() {
+    use alloc::string::ToString;
+    use miniarg::man::ManPage;
+    let page = ManPage::new("my\"tool", &[]).to_string();
+    assert!(page.starts_with(".TH \"MY\\(dqTOOL\" \"1\"\n"));
+}
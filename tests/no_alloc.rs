@@ -1,7 +1,16 @@
 //! Integration tests for the no alloc case.
 //! These are almost the same as main file, but without `collect`.
 #![no_std]
-use miniarg::{ParseError, parse};
+// `&cmdline` also works if the cmdline is a `String`
+#![allow(clippy::needless_borrow)]
+use miniarg::config::{merge, parse_config};
+use miniarg::help::Help;
+use miniarg::response_files::ResponseFiles;
+use miniarg::split_args::SplitArgs;
+use miniarg::{
+    Arity, Builtins, InlineStr, Occurrences, ParseError, ParseOutcome, ParseWarning, parse,
+    parse_from_iter, parse_strict,
+};
 
 #[test]
 /// Just calling a binary should produce an empty result.
@@ -68,3 +77,549 @@ fn missing_key() {
         ParseError::NotAKey("value")
     );
 }
+
+#[test]
+/// An unterminated quote should produce an error in strict mode.
+fn strict_unterminated_quote() {
+    let cmdline = "executable -key 'value";
+    let mut iter = parse_strict(cmdline, &["key"]);
+    assert_eq!(
+        iter.next(),
+        Some(Err(ParseError::UnterminatedQuote { start: 16 }))
+    );
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// An error in the name of the program should be reported, too.
+fn strict_unterminated_quote_in_program_name() {
+    let cmdline = "\"executable -key value";
+    let mut iter = parse_strict(cmdline, &["key"]);
+    assert_eq!(iter.program_name(), None);
+    assert_eq!(
+        iter.next(),
+        Some(Err(ParseError::UnterminatedQuote { start: 0 }))
+    );
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// An inline error should contain a copy of the token.
+fn into_inline() {
+    let cmdline = "executable -invalid";
+    let error = parse(cmdline, &["key"])
+        .next()
+        .unwrap()
+        .unwrap_err()
+        .into_inline::<16>();
+    assert_eq!(error, ParseError::UnknownKey(InlineStr::new("invalid")));
+    let ParseError::UnknownKey(key) = error else {
+        panic!("unexpected error {error:?}");
+    };
+    assert_eq!(key.as_str(), "invalid");
+    assert!(!key.is_truncated());
+}
+
+#[test]
+/// Long tokens should be truncated at a char boundary.
+fn into_inline_truncated() {
+    let cmdline = "executable -invälid";
+    let error = parse(cmdline, &["key"])
+        .next()
+        .unwrap()
+        .unwrap_err()
+        .into_inline::<4>();
+    let ParseError::UnknownKey(key) = error else {
+        panic!("unexpected error {error:?}");
+    };
+    assert_eq!(key.as_str(), "inv");
+    assert!(key.is_truncated());
+}
+
+#[test]
+/// Missing required keys should be reported at the end.
+fn missing_required_key() {
+    let cmdline = "executable -key value";
+    let mut iter = parse(cmdline, &["key", "other"]).required(&["other"]);
+    assert_eq!(iter.next(), Some(Ok((&"key", "value"))));
+    assert_eq!(iter.next(), Some(Err(ParseError::MissingKey("other"))));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// A duplicate key should produce an error and its value should be skipped.
+fn duplicate_key() {
+    let cmdline = "executable -key value1 -key value2";
+    let mut iter = parse(cmdline, &["key"]).occurrences(&[("key", Occurrences::AT_MOST_ONCE)]);
+    assert_eq!(iter.next(), Some(Ok((&"key", "value1"))));
+    assert_eq!(iter.next(), Some(Err(ParseError::DuplicateKey("key"))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// Absent keys should get their default values at the end.
+fn defaults() {
+    let cmdline = "executable -key value -port";
+    let mut iter = parse(cmdline, &["key", "port", "host"])
+        .defaults(&[("port", "8080"), ("host", "localhost")])
+        .required(&["other"]);
+    assert_eq!(iter.next(), Some(Ok((&"key", "value"))));
+    assert_eq!(iter.next(), Some(Ok((&"port", ""))));
+    assert_eq!(iter.next(), Some(Ok((&"host", "localhost"))));
+    assert_eq!(iter.next(), Some(Err(ParseError::MissingKey("other"))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// Absent keys should be taken from a custom environment before the defaults.
+fn env() {
+    let env = |name: &str| match name {
+        "APP_PORT" => Some("80"),
+        "APP_CONFIG" => Some("app.conf"),
+        _ => None,
+    };
+    let cmdline = "executable -key value";
+    let mut iter = parse(cmdline, &["key", "port", "host", "config"])
+        .env(&[
+            ("key", "APP_KEY"),
+            ("port", "APP_PORT"),
+            ("config", "APP_CONFIG"),
+        ])
+        .env_source(&env)
+        .defaults(&[("port", "8080"), ("host", "localhost")])
+        .required(&["config"]);
+    assert_eq!(iter.next(), Some(Ok((&"key", "value"))));
+    assert_eq!(iter.next(), Some(Ok((&"port", "80"))));
+    assert_eq!(iter.next(), Some(Ok((&"config", "app.conf"))));
+    assert_eq!(iter.next(), Some(Ok((&"host", "localhost"))));
+    assert_eq!(iter.next(), None);
+}
+
+/// Response files for the tests below.
+fn load(path: &str) -> Option<&'static str> {
+    match path {
+        "args.rsp" => Some("-key 'a value'\n@nested.rsp\n-other c"),
+        "nested.rsp" => Some("-key b"),
+        "cycle.rsp" => Some("-key a @cycle2.rsp"),
+        "cycle2.rsp" => Some("@cycle.rsp -other b"),
+        _ => None,
+    }
+}
+
+#[test]
+/// Response files should be spliced into the args.
+fn response_files() {
+    let args = ResponseFiles::new(SplitArgs::new("@executable @args.rsp -other d"), load);
+    let mut iter = parse_from_iter(args, &["key", "other"]);
+    assert_eq!(iter.next(), Some(Ok((&"key", "a value"))));
+    assert_eq!(iter.next(), Some(Ok((&"key", "b"))));
+    assert_eq!(iter.next(), Some(Ok((&"other", "c"))));
+    assert_eq!(iter.next(), Some(Ok((&"other", "d"))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// Cycles and missing response files should produce errors.
+fn response_file_errors() {
+    let mut args = ResponseFiles::new(SplitArgs::new("executable @cycle.rsp @missing.rsp"), load);
+    assert_eq!(args.next(), Some(Ok("executable")));
+    assert_eq!(args.next(), Some(Ok("-key")));
+    assert_eq!(args.next(), Some(Ok("a")));
+    assert_eq!(
+        args.next(),
+        Some(Err(ParseError::ResponseFileCycle("cycle.rsp")))
+    );
+    assert_eq!(args.next(), Some(Ok("-other")));
+    assert_eq!(args.next(), Some(Ok("b")));
+    assert_eq!(
+        args.next(),
+        Some(Err(ParseError::UnreadableResponseFile("missing.rsp")))
+    );
+    assert_eq!(args.next(), None);
+}
+
+#[test]
+/// Both separators, comments and empty lines should be supported.
+fn config() {
+    let config = "  # comment\n\nkey value with spaces\nother=b\r\nkey = c\nother\n= d\nunknown e";
+    let mut iter = parse_config(config, &["key", "other"]);
+    assert_eq!(iter.next(), Some(Ok((&"key", "value with spaces"))));
+    assert_eq!(iter.next(), Some(Ok((&"other", "b"))));
+    assert_eq!(iter.next(), Some(Ok((&"key", "c"))));
+    assert_eq!(iter.next(), Some(Ok((&"other", ""))));
+    assert_eq!(iter.next(), Some(Err(ParseError::NotAKey("= d"))));
+    assert_eq!(iter.next(), Some(Err(ParseError::UnknownKey("unknown"))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// Keys on the cmdline should override all of their occurrences in the config file.
+fn merge_config() {
+    let file = [(&"key", "a"), (&"other", "b"), (&"key", "c")];
+    let cmdline = [(&"key", "d")];
+    let mut iter = merge(&file, &cmdline);
+    assert_eq!(iter.next(), Some(&(&"other", "b")));
+    assert_eq!(iter.next(), Some(&(&"key", "d")));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// Flags shouldn't take a value and should be negatable.
+fn flags() {
+    let cmdline = "executable -color -key value -no-color -no-key x -verbose";
+    let mut iter =
+        parse(cmdline, &["color", "key", "no-key", "verbose"]).flags(&["color", "key", "verbose"]);
+    assert_eq!(iter.next(), Some(Ok((&"color", "true"))));
+    assert_eq!(iter.next(), Some(Ok((&"key", "true"))));
+    assert_eq!(iter.next(), Some(Err(ParseError::NotAKey("value"))));
+    assert_eq!(iter.next(), Some(Ok((&"color", "false"))));
+    assert_eq!(iter.next(), Some(Ok((&"no-key", "x"))));
+    assert_eq!(iter.next(), Some(Ok((&"verbose", "true"))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// Only flags should be negatable.
+fn negated_non_flag() {
+    let cmdline = "executable -no-key value";
+    let mut iter = parse(cmdline, &["key"]);
+    assert_eq!(iter.next(), Some(Err(ParseError::UnknownKey("no-key"))));
+}
+
+#[test]
+/// Rejected flags shouldn't skip the next argument.
+fn duplicate_flag() {
+    let cmdline = "executable -color -no-color -key value";
+    let mut iter = parse(cmdline, &["color", "key"])
+        .flags(&["color"])
+        .occurrences(&[("color", Occurrences::AT_MOST_ONCE)]);
+    assert_eq!(iter.next(), Some(Ok((&"color", "true"))));
+    assert_eq!(iter.next(), Some(Err(ParseError::DuplicateKey("no-color"))));
+    assert_eq!(iter.next(), Some(Ok((&"key", "value"))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// Keys with a fixed arity should take that many values, even if they start with a dash.
+fn fixed_arity() {
+    let cmdline = "executable -offset -1 -16 -key value -offset 1";
+    let mut iter = parse(cmdline, &["offset", "key"]).arity(&[("offset", Arity::Exactly(2))]);
+    assert_eq!(iter.next(), Some(Ok((&"offset", "-1"))));
+    assert_eq!(iter.next(), Some(Ok((&"offset", "-16"))));
+    assert_eq!(iter.next(), Some(Ok((&"key", "value"))));
+    assert_eq!(iter.next(), Some(Ok((&"offset", "1"))));
+    assert_eq!(
+        iter.next(),
+        Some(Err(ParseError::MissingValue {
+            key: "offset",
+            expected: 2,
+            got: 1
+        }))
+    );
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// Greedy keys should take all values until the next key.
+fn greedy_arity() {
+    let cmdline = "executable -define A B -define -key value -define C";
+    let mut iter = parse(cmdline, &["define", "key"]).arity(&[("define", Arity::Greedy)]);
+    assert_eq!(iter.next(), Some(Ok((&"define", "A"))));
+    assert_eq!(iter.next(), Some(Ok((&"define", "B"))));
+    assert_eq!(
+        iter.next(),
+        Some(Err(ParseError::MissingValue {
+            key: "define",
+            expected: 1,
+            got: 0
+        }))
+    );
+    assert_eq!(iter.next(), Some(Ok((&"key", "value"))));
+    assert_eq!(iter.next(), Some(Ok((&"define", "C"))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// All values of a rejected key should be skipped.
+fn duplicate_key_arity() {
+    let cmdline = "executable -range 1 2 -range 3 4 -define A B -define C -key value";
+    let mut iter = parse(cmdline, &["range", "define", "key"])
+        .arity(&[("range", Arity::Exactly(2)), ("define", Arity::Greedy)])
+        .occurrences(&[
+            ("range", Occurrences::AT_MOST_ONCE),
+            ("define", Occurrences::AT_MOST_ONCE),
+        ]);
+    assert_eq!(iter.next(), Some(Ok((&"range", "1"))));
+    assert_eq!(iter.next(), Some(Ok((&"range", "2"))));
+    assert_eq!(iter.next(), Some(Err(ParseError::DuplicateKey("range"))));
+    assert_eq!(iter.next(), Some(Ok((&"define", "A"))));
+    assert_eq!(iter.next(), Some(Ok((&"define", "B"))));
+    assert_eq!(iter.next(), Some(Err(ParseError::DuplicateKey("define"))));
+    assert_eq!(iter.next(), Some(Ok((&"key", "value"))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// In strict mode, an empty value should be different from a missing one.
+fn strict_values() {
+    let cmdline = "executable -key '' -key";
+    let mut iter = parse(cmdline, &["key"]).strict_values();
+    assert_eq!(iter.next(), Some(Ok((&"key", ""))));
+    assert_eq!(
+        iter.next(),
+        Some(Err(ParseError::MissingValue {
+            key: "key",
+            expected: 1,
+            got: 0
+        }))
+    );
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// By default, a missing value should be empty.
+fn compat_values() {
+    let cmdline = "executable -key '' -key";
+    let mut iter = parse(cmdline, &["key"]);
+    assert_eq!(iter.next(), Some(Ok((&"key", ""))));
+    assert_eq!(iter.next(), Some(Ok((&"key", ""))));
+    assert_eq!(iter.next(), None);
+
+    let mut iter = parse("executable -help -verbose", &["help", "verbose"]);
+    assert_eq!(iter.next(), Some(Ok((&"help", ""))));
+    assert_eq!(iter.next(), Some(Ok((&"verbose", ""))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// Negative numbers and `-` should be values.
+fn hyphen_values() {
+    let cmdline = "executable -offset -16 -scale -.5 -input - -offset -0x10";
+    let mut iter = parse(cmdline, &["offset", "scale", "input"]);
+    assert_eq!(iter.next(), Some(Ok((&"offset", "-16"))));
+    assert_eq!(iter.next(), Some(Ok((&"scale", "-.5"))));
+    assert_eq!(iter.next(), Some(Ok((&"input", "-"))));
+    assert_eq!(iter.next(), Some(Ok((&"offset", "-0x10"))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// Values that look like keys should end the values of the last key.
+fn key_instead_of_value() {
+    let cmdline = "executable -offset -key value -range 1 -key value";
+    let mut iter =
+        parse(cmdline, &["offset", "key", "range"]).arity(&[("range", Arity::Exactly(2))]);
+    assert_eq!(iter.next(), Some(Ok((&"offset", ""))));
+    assert_eq!(iter.next(), Some(Ok((&"key", "value"))));
+    assert_eq!(iter.next(), Some(Ok((&"range", "1"))));
+    assert_eq!(
+        iter.next(),
+        Some(Err(ParseError::MissingValue {
+            key: "range",
+            expected: 2,
+            got: 1
+        }))
+    );
+    assert_eq!(iter.next(), Some(Ok((&"key", "value"))));
+    assert_eq!(iter.next(), None);
+
+    let mut iter = parse("executable -offset -key value", &["offset", "key"]).strict_values();
+    assert_eq!(
+        iter.next(),
+        Some(Err(ParseError::MissingValue {
+            key: "offset",
+            expected: 1,
+            got: 0
+        }))
+    );
+    assert_eq!(iter.next(), Some(Ok((&"key", "value"))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// Keys allowing hyphen values should take them as they are.
+fn allow_hyphen_values() {
+    let cmdline = "executable -args -v -q -key value";
+    let mut iter = parse(cmdline, &["args", "key"])
+        .arity(&[("args", Arity::Greedy)])
+        .allow_hyphen_values(&["args"]);
+    assert_eq!(iter.next(), Some(Ok((&"args", "-v"))));
+    assert_eq!(iter.next(), Some(Ok((&"args", "-q"))));
+    assert_eq!(iter.next(), Some(Ok((&"args", "-key"))));
+    assert_eq!(iter.next(), Some(Ok((&"args", "value"))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// Negative numbers and `-` aren't keys.
+fn hyphen_value_without_key() {
+    let cmdline = "executable -16 -";
+    let mut iter = parse(cmdline, &["key"]);
+    assert_eq!(iter.next(), Some(Err(ParseError::NotAKey("-16"))));
+    assert_eq!(iter.next(), Some(Err(ParseError::NotAKey("-"))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// Keys should be able to use other prefixes.
+fn prefixes() {
+    let cmdline = "executable /key value -key /other +other -1 /no-color /";
+    let mut iter = parse(cmdline, &["key", "other", "color"])
+        .prefixes(&["/", "+"])
+        .flags(&["color"]);
+    assert_eq!(iter.next(), Some(Ok((&"key", "value"))));
+    assert_eq!(iter.next(), Some(Err(ParseError::NotAKey("-key"))));
+    assert_eq!(iter.next(), Some(Ok((&"other", ""))));
+    assert_eq!(iter.next(), Some(Ok((&"other", "-1"))));
+    assert_eq!(iter.next(), Some(Ok((&"color", "false"))));
+    assert_eq!(iter.next(), Some(Err(ParseError::NotAKey("/"))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// The longest matching prefix should be used.
+fn longest_prefix() {
+    let cmdline = "executable --key -value -other --";
+    let mut iter = parse(cmdline, &["key", "other"]).prefixes(&["-", "--"]);
+    assert_eq!(iter.next(), Some(Ok((&"key", ""))));
+    assert_eq!(iter.next(), Some(Err(ParseError::UnknownKey("value"))));
+    assert_eq!(iter.next(), Some(Ok((&"other", "--"))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// With an empty prefix, all arguments in the place of keys should be keys.
+fn empty_prefix() {
+    let cmdline = "executable key value other -value unknown value";
+    let mut iter = parse(cmdline, &["key", "other"]).prefixes(&[""]);
+    assert_eq!(iter.next(), Some(Ok((&"key", "value"))));
+    assert_eq!(iter.next(), Some(Ok((&"other", "-value"))));
+    assert_eq!(iter.next(), Some(Err(ParseError::UnknownKey("unknown"))));
+    assert_eq!(iter.next(), Some(Err(ParseError::UnknownKey("value"))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// The program name should be available.
+fn program_name() {
+    let iter = parse("executable -key value", &["key"]);
+    assert_eq!(iter.program_name(), Some("executable"));
+    let iter = parse("", &["key"]);
+    assert_eq!(iter.program_name(), None);
+}
+
+#[test]
+/// Without a program name, the first argument should be parsed, too.
+fn without_program_name() {
+    let cmdline = "key value other x";
+    let mut iter = parse(cmdline, &["key", "other"])
+        .without_program_name()
+        .prefixes(&[""]);
+    assert_eq!(iter.program_name(), None);
+    assert_eq!(iter.next(), Some(Ok((&"key", "value"))));
+    assert_eq!(iter.next(), Some(Ok((&"other", "x"))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// Deprecated keys should be parsed and reported once.
+fn deprecated() {
+    let cmdline = "executable -old a -new b -old c";
+    let mut iter =
+        parse(cmdline, &["new", "old", "older"]).deprecated(&[("old", "use -new"), ("older", "")]);
+    assert_eq!(iter.warnings().next(), None);
+    assert_eq!(iter.next(), Some(Ok((&"old", "a"))));
+    assert_eq!(iter.next(), Some(Ok((&"new", "b"))));
+    assert_eq!(iter.next(), Some(Ok((&"old", "c"))));
+    assert_eq!(iter.next(), None);
+    let mut warnings = iter.warnings();
+    assert_eq!(
+        warnings.next(),
+        Some(ParseWarning::Deprecated {
+            key: "old",
+            note: "use -new"
+        })
+    );
+    assert_eq!(warnings.next(), None);
+}
+
+#[test]
+/// `-help` and `-version` should only be handled if they aren't declared.
+fn builtins() {
+    let help = Help::new(&[("key", "a key")]);
+    let cmdline = "executable -key value -version -key other";
+    let mut iter = Builtins::new(parse(cmdline, &["key"]), help, "1.0");
+    assert!(matches!(
+        iter.next(),
+        Some(Ok(ParseOutcome::Arg((&"key", "value"))))
+    ));
+    assert!(matches!(
+        iter.next(),
+        Some(Ok(ParseOutcome::Version("1.0")))
+    ));
+    assert!(iter.next().is_none());
+
+    let mut iter = Builtins::new(parse("executable -help x", &["help"]), help, "1.0");
+    assert!(matches!(
+        iter.next(),
+        Some(Ok(ParseOutcome::Arg((&"help", "x"))))
+    ));
+    assert!(iter.next().is_none());
+}
+
+#[test]
+/// More occurrence limits than can be enforced should be reported.
+fn too_many_occurrence_limits() {
+    let limits = [("key", Occurrences::AT_MOST_ONCE); 33];
+    let mut iter = parse("executable -key a -key b", &["key"]).occurrences(&limits);
+    assert_eq!(iter.next(), Some(Ok((&"key", "a"))));
+    assert_eq!(iter.next(), Some(Err(ParseError::DuplicateKey("key"))));
+    assert_eq!(iter.next(), Some(Err(ParseError::UntrackedKey("key"))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+/// Keys should be counted beyond 255 occurrences.
+fn many_occurrences() {
+    let args = core::iter::once("executable")
+        .chain(core::iter::repeat_n(["-key", "value"], 300).flatten());
+    let limits = [("key", Occurrences::at_least(300))];
+    let iter = parse_from_iter(args, &["key"]).occurrences(&limits);
+    assert_eq!(iter.map(Result::unwrap).count(), 300);
+}
+
+#[test]
+/// The values should be grouped by the occurrence of their key.
+fn grouped() {
+    let options = ["define", "verbose", "level"];
+    let args = |cmdline| {
+        parse(cmdline, &options)
+            .arity(&[("define", Arity::Greedy)])
+            .flags(&["verbose"])
+            .defaults(&[("level", "1")])
+            .grouped()
+    };
+    let mut iter = args("executable -define A B -define C -verbose");
+    assert_eq!(iter.next(), Some(Ok((&"define", 0, "A"))));
+    assert_eq!(iter.next(), Some(Ok((&"define", 0, "B"))));
+    assert_eq!(iter.next(), Some(Ok((&"define", 1, "C"))));
+    assert_eq!(iter.next(), Some(Ok((&"verbose", 2, "true"))));
+    assert_eq!(iter.next(), Some(Ok((&"level", 3, "1"))));
+    assert_eq!(iter.next(), None);
+    let mut iter = args("executable -unknown -define A -define B C");
+    assert_eq!(iter.next(), Some(Err(ParseError::UnknownKey("unknown"))));
+    assert_eq!(iter.next(), Some(Ok((&"define", 0, "A"))));
+    assert_eq!(iter.next(), Some(Ok((&"define", 1, "B"))));
+    assert_eq!(iter.next(), Some(Ok((&"define", 1, "C"))));
+    assert_eq!(iter.next(), Some(Ok((&"level", 2, "1"))));
+    assert_eq!(iter.next(), None);
+
+    let mut iter = parse("executable -a 1 -a 2 -b 3", &["a", "b"])
+        .occurrences(&[("a", Occurrences::AT_MOST_ONCE)])
+        .grouped();
+    assert_eq!(iter.next(), Some(Ok((&"a", 0, "1"))));
+    assert_eq!(iter.next(), Some(Err(ParseError::DuplicateKey("a"))));
+    assert_eq!(iter.next(), Some(Ok((&"b", 1, "3"))));
+    assert_eq!(iter.next(), None);
+}